@@ -0,0 +1,70 @@
+//! Compare against another revision with criterion's saved baselines, e.g.
+//! run `cargo bench --bench next_ssg -- --save-baseline before` on the parent
+//! commit, then `cargo bench --bench next_ssg -- --baseline before`.
+
+use std::sync::Arc;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use next_binding::swc::core::{
+    common::{FileName, FilePathMapping, Mark, SourceMap, GLOBALS},
+    ecma::ast::{EsVersion, Module},
+    ecma::parser::{parse_file_as_module, EsConfig, Syntax},
+    ecma::transforms::base::resolver,
+    ecma::visit::FoldWith,
+};
+use next_swc::next_ssg::next_ssg;
+
+/// A page whose data function depends on a chain of `depth` helpers, each one
+/// only used by the previous one.
+fn page_with_helper_chain(depth: usize) -> String {
+    let mut src = String::new();
+    for i in 0..depth {
+        src.push_str(&format!(
+            "function helper{}(v) {{ return helper{}(v + 1) }}\n",
+            i,
+            i + 1
+        ));
+    }
+    src.push_str(&format!("function helper{}(v) {{ return v }}\n", depth));
+    src.push_str(
+        "export async function getStaticProps() {\n  return { props: { value: helper0(0) } }\n}\n",
+    );
+    src.push_str("export default function Page({ value }) {\n  return <div>{value}</div>\n}\n");
+    src
+}
+
+fn parse(src: String) -> Module {
+    let cm = Arc::new(SourceMap::new(FilePathMapping::empty()));
+    let fm = cm.new_source_file(FileName::Anon, src);
+
+    parse_file_as_module(
+        &fm,
+        Syntax::Es(EsConfig {
+            jsx: true,
+            ..Default::default()
+        }),
+        EsVersion::latest(),
+        None,
+        &mut vec![],
+    )
+    .unwrap()
+}
+
+fn helper_chain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("next_ssg/helper_chain");
+    for depth in [10, 100, 1000] {
+        let module = parse(page_with_helper_chain(depth));
+
+        GLOBALS.set(&Default::default(), || {
+            // Resolve once, like the transform pipeline does before next_ssg.
+            let module = module.fold_with(&mut resolver(Mark::new(), Mark::new(), false));
+            group.bench_with_input(BenchmarkId::from_parameter(depth), &module, |b, module| {
+                b.iter(|| black_box(module.clone().fold_with(&mut next_ssg(Default::default()))))
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, helper_chain);
+criterion_main!(benches);
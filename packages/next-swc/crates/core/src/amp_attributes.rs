@@ -1,17 +1,361 @@
+use fxhash::FxHashMap;
+
 use next_binding::swc::core::{
-    ecma::ast::{Ident, JSXAttr, JSXAttrName, JSXAttrOrSpread, JSXElementName, JSXOpeningElement},
+    common::errors::HANDLER,
+    ecma::ast::{
+        Bool, Decl, ExportSpecifier, Expr, Ident, JSXAttr, JSXAttrName, JSXAttrOrSpread,
+        JSXAttrValue, JSXElementName, JSXNamespacedName, JSXOpeningElement, KeyValueProp, Lit,
+        Module, ModuleDecl, ModuleExportName, ModuleItem, Pat, Prop, PropName, PropOrSpread, Str,
+    },
     ecma::atoms::JsWord,
-    ecma::visit::Fold,
+    ecma::visit::{Fold, FoldWith},
 };
 
+use crate::page_config::{collect_local_inits, unwrap_expr};
+
 pub fn amp_attributes() -> impl Fold {
     AmpAttributePatcher::default()
 }
 
 #[derive(Debug, Default)]
-struct AmpAttributePatcher {}
+struct AmpAttributePatcher {
+    /// `config.amp` of the page, in which case intrinsic elements are
+    /// validated.
+    amp: Option<Amp>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Amp {
+    /// `amp: true`, where the page is never hydrated.
+    Only,
+    /// `amp: 'hybrid'`, or any other truthy value.
+    Hybrid,
+}
+
+/// `type`s of `<script>` tags allowed in AMP pages.
+const ALLOWED_SCRIPT_TYPES: &[&str] = &["application/json", "application/ld+json"];
+
+/// Attributes allowed on every element, as lowercase names without dashes so
+/// that React's camelCase names match too.
+const GLOBAL_ATTRIBUTES: &[&str] = &[
+    // HTML
+    "accesskey",
+    "autocapitalize",
+    "autofocus",
+    "class",
+    "classname",
+    "contenteditable",
+    "dir",
+    "draggable",
+    "enterkeyhint",
+    "hidden",
+    "id",
+    "inputmode",
+    "itemid",
+    "itemprop",
+    "itemref",
+    "itemscope",
+    "itemtype",
+    "lang",
+    "nonce",
+    "role",
+    "slot",
+    "spellcheck",
+    "style",
+    "tabindex",
+    "title",
+    "translate",
+    // AMP
+    "fallback",
+    "height",
+    "heights",
+    "layout",
+    "media",
+    "noloading",
+    "on",
+    "placeholder",
+    "sizes",
+    "width",
+    // React
+    "children",
+    "dangerouslysetinnerhtml",
+    "key",
+    "ref",
+    "suppresscontenteditablewarning",
+    "suppresshydrationwarning",
+];
+
+/// Attribute name prefixes allowed on every element.
+const GLOBAL_ATTRIBUTE_PREFIXES: &[&str] = &["aria-", "data-"];
+
+/// Attributes allowed on specific elements, in addition to
+/// [GLOBAL_ATTRIBUTES].
+const ELEMENT_ATTRIBUTES: &[(&str, &[&str])] = &[
+    (
+        "a",
+        &[
+            "download",
+            "href",
+            "hreflang",
+            "name",
+            "referrerpolicy",
+            "rel",
+            "target",
+            "type",
+        ],
+    ),
+    (
+        "area",
+        &[
+            "alt", "coords", "download", "href", "rel", "shape", "target",
+        ],
+    ),
+    ("blockquote", &["cite"]),
+    (
+        "button",
+        &[
+            "disabled",
+            "form",
+            "formaction",
+            "formenctype",
+            "formmethod",
+            "formnovalidate",
+            "formtarget",
+            "name",
+            "type",
+            "value",
+        ],
+    ),
+    ("col", &["span"]),
+    ("colgroup", &["span"]),
+    ("data", &["value"]),
+    ("del", &["cite", "datetime"]),
+    ("details", &["open"]),
+    ("fieldset", &["disabled", "form", "name"]),
+    (
+        "form",
+        &[
+            "acceptcharset",
+            "action",
+            "actionxhr",
+            "autocomplete",
+            "customvalidationreporting",
+            "enctype",
+            "method",
+            "name",
+            "novalidate",
+            "target",
+            "verifyxhr",
+        ],
+    ),
+    ("html", &["amp", "transformed"]),
+    (
+        "img",
+        &[
+            "alt",
+            "decoding",
+            "loading",
+            "referrerpolicy",
+            "src",
+            "srcset",
+        ],
+    ),
+    (
+        "input",
+        &[
+            "accept",
+            "alt",
+            "autocomplete",
+            "checked",
+            "defaultchecked",
+            "defaultvalue",
+            "dirname",
+            "disabled",
+            "form",
+            "formaction",
+            "formenctype",
+            "formmethod",
+            "formnovalidate",
+            "formtarget",
+            "list",
+            "max",
+            "maxlength",
+            "min",
+            "minlength",
+            "multiple",
+            "name",
+            "noverify",
+            "pattern",
+            "readonly",
+            "required",
+            "size",
+            "step",
+            "type",
+            "value",
+        ],
+    ),
+    ("ins", &["cite", "datetime"]),
+    ("label", &["for", "form", "htmlfor"]),
+    ("li", &["value"]),
+    (
+        "link",
+        &[
+            "as",
+            "color",
+            "crossorigin",
+            "href",
+            "hreflang",
+            "rel",
+            "type",
+        ],
+    ),
+    (
+        "meta",
+        &["charset", "content", "httpequiv", "name", "property"],
+    ),
+    ("meter", &["high", "low", "max", "min", "optimum", "value"]),
+    ("ol", &["reversed", "start", "type"]),
+    ("optgroup", &["disabled", "label"]),
+    ("option", &["disabled", "label", "selected", "value"]),
+    ("output", &["for", "form", "htmlfor", "name"]),
+    ("progress", &["max", "value"]),
+    ("q", &["cite"]),
+    (
+        "script",
+        &[
+            "async",
+            "crossorigin",
+            "customelement",
+            "customtemplate",
+            "nomodule",
+            "src",
+            "type",
+        ],
+    ),
+    (
+        "select",
+        &[
+            "autocomplete",
+            "defaultvalue",
+            "disabled",
+            "form",
+            "multiple",
+            "name",
+            "required",
+            "size",
+            "value",
+        ],
+    ),
+    ("source", &["src", "srcset", "type"]),
+    (
+        "style",
+        &["ampboilerplate", "ampcustom", "ampkeyframes", "ampruntime"],
+    ),
+    ("table", &["border"]),
+    ("td", &["colspan", "headers", "rowspan"]),
+    ("template", &["type"]),
+    (
+        "textarea",
+        &[
+            "autocomplete",
+            "cols",
+            "defaultvalue",
+            "dirname",
+            "disabled",
+            "form",
+            "maxlength",
+            "minlength",
+            "name",
+            "readonly",
+            "required",
+            "rows",
+            "value",
+            "wrap",
+        ],
+    ),
+    ("th", &["abbr", "colspan", "headers", "rowspan", "scope"]),
+    ("time", &["datetime"]),
+    ("track", &["default", "kind", "label", "src", "srclang"]),
+];
+
+/// HTML elements which only take [GLOBAL_ATTRIBUTES]. Elements which are
+/// neither listed here nor in [ELEMENT_ATTRIBUTES], like SVG elements, aren't
+/// validated.
+const GLOBAL_ATTRIBUTE_ELEMENTS: &[&str] = &[
+    "abbr",
+    "address",
+    "article",
+    "aside",
+    "b",
+    "bdi",
+    "bdo",
+    "body",
+    "br",
+    "caption",
+    "cite",
+    "code",
+    "dd",
+    "dfn",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hgroup",
+    "hr",
+    "i",
+    "kbd",
+    "legend",
+    "main",
+    "mark",
+    "nav",
+    "noscript",
+    "p",
+    "picture",
+    "pre",
+    "rp",
+    "rt",
+    "ruby",
+    "s",
+    "samp",
+    "section",
+    "small",
+    "span",
+    "strong",
+    "sub",
+    "summary",
+    "sup",
+    "tbody",
+    "tfoot",
+    "thead",
+    "title",
+    "tr",
+    "u",
+    "ul",
+    "var",
+    "wbr",
+];
 
 impl Fold for AmpAttributePatcher {
+    fn fold_module(&mut self, module: Module) -> Module {
+        let local_inits = collect_local_inits(&module.body);
+        self.amp = module
+            .body
+            .iter()
+            .find_map(|item| amp_config(item, &local_inits));
+        module.fold_children_with(self)
+    }
+
     fn fold_jsx_opening_element(&mut self, node: JSXOpeningElement) -> JSXOpeningElement {
         let JSXOpeningElement {
             name,
@@ -23,6 +367,7 @@ impl Fold for AmpAttributePatcher {
         let n = name.clone();
 
         if let JSXElementName::Ident(Ident { sym, .. }) = name {
+            let is_intrinsic = sym.starts_with(|c: char| c.is_ascii_lowercase());
             if sym.starts_with("amp-") {
                 for i in &mut attrs {
                     if let JSXAttrOrSpread::JSXAttr(JSXAttr {
@@ -36,20 +381,41 @@ impl Fold for AmpAttributePatcher {
                         value,
                     }) = &i
                     {
-                        if sym as &str == "className" {
-                            *i = JSXAttrOrSpread::JSXAttr(JSXAttr {
-                                name: JSXAttrName::Ident(Ident {
-                                    sym: JsWord::from("class"),
-                                    span: *s,
-                                    optional: *o,
-                                }),
-                                span: *span,
-                                value: value.clone(),
-                            })
-                        }
+                        let html_name = match sym as &str {
+                            "className" => "class",
+                            "htmlFor" => "for",
+                            _ => continue,
+                        };
+                        *i = JSXAttrOrSpread::JSXAttr(JSXAttr {
+                            name: JSXAttrName::Ident(Ident {
+                                sym: JsWord::from(html_name),
+                                span: *s,
+                                optional: *o,
+                            }),
+                            span: *span,
+                            value: value.clone(),
+                        })
                     }
                 }
             }
+
+            match self.amp {
+                Some(amp) if is_intrinsic => {
+                    if &*sym == "script" && !is_allowed_script(&attrs) {
+                        HANDLER.with(|handler| {
+                            handler
+                                .struct_span_err(
+                                    span,
+                                    "`<script>` tags are not allowed in AMP pages. Use AMP \
+                                     components instead.",
+                                )
+                                .emit()
+                        });
+                    }
+                    validate_attrs(&sym, &attrs, amp);
+                }
+                _ => {}
+            }
         }
 
         JSXOpeningElement {
@@ -61,3 +427,164 @@ impl Fold for AmpAttributePatcher {
         }
     }
 }
+
+/// `config.amp` if `item` exports a `config` with `amp` set to a value other
+/// than `false`, either as `export const config = { ... }` or as a local
+/// binding exported with `export { config }`.
+fn amp_config(item: &ModuleItem, local_inits: &FxHashMap<JsWord, Box<Expr>>) -> Option<Amp> {
+    match item {
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => match &export.decl {
+            Decl::Var(var_decl) => var_decl.decls.iter().find_map(|decl| match &decl.name {
+                Pat::Ident(ident) if &*ident.id.sym == "config" => {
+                    decl.init.as_deref().and_then(amp_of)
+                }
+                _ => None,
+            }),
+            _ => None,
+        },
+        ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) if export.src.is_none() => {
+            export.specifiers.iter().find_map(|specifier| {
+                let specifier = match specifier {
+                    ExportSpecifier::Named(specifier) => specifier,
+                    _ => return None,
+                };
+                let orig = match &specifier.orig {
+                    ModuleExportName::Ident(ident) => &ident.sym,
+                    ModuleExportName::Str(..) => return None,
+                };
+                let exported = match &specifier.exported {
+                    Some(ModuleExportName::Ident(ident)) => &ident.sym,
+                    Some(ModuleExportName::Str(..)) => return None,
+                    None => orig,
+                };
+                if &**exported != "config" {
+                    return None;
+                }
+                local_inits.get(orig).and_then(|init| amp_of(init))
+            })
+        }
+        _ => None,
+    }
+}
+
+/// `amp` of `config` if it is an object with `amp` set to a value other than
+/// `false`.
+fn amp_of(config: &Expr) -> Option<Amp> {
+    let obj = match unwrap_expr(config) {
+        Expr::Object(obj) => obj,
+        _ => return None,
+    };
+
+    obj.props.iter().find_map(|prop| match prop {
+        PropOrSpread::Prop(prop) => match &**prop {
+            Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(Ident { sym, .. }),
+                value,
+            }) if &**sym == "amp" => match unwrap_expr(value) {
+                Expr::Lit(Lit::Bool(Bool { value: false, .. })) => None,
+                Expr::Lit(Lit::Bool(Bool { value: true, .. })) => Some(Amp::Only),
+                _ => Some(Amp::Hybrid),
+            },
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+fn attr_name(attr: &JSXAttr) -> String {
+    match &attr.name {
+        JSXAttrName::Ident(ident) => ident.sym.to_string(),
+        JSXAttrName::JSXNamespacedName(JSXNamespacedName { ns, name }) => {
+            format!("{}:{}", ns.sym, name.sym)
+        }
+    }
+}
+
+/// Whether a `<script>` is JSON data or loads an AMP extension.
+fn is_allowed_script(attrs: &[JSXAttrOrSpread]) -> bool {
+    attrs.iter().any(|attr| match attr {
+        JSXAttrOrSpread::JSXAttr(attr) => match &*attr_name(attr) {
+            "type" => matches!(
+                &attr.value,
+                Some(JSXAttrValue::Lit(Lit::Str(Str { value, .. })))
+                    if ALLOWED_SCRIPT_TYPES.contains(&&**value)
+            ),
+            "custom-element" | "custom-template" => true,
+            _ => false,
+        },
+        _ => false,
+    })
+}
+
+/// Lowercases `name` and drops dashes, so `acceptCharset` and
+/// `accept-charset` compare equal.
+fn normalize_attr_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Whether `name` is a React event handler, like `onClick`.
+fn is_event_handler(name: &str) -> bool {
+    name.len() > 2
+        && name.starts_with("on")
+        && name[2..].starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+/// Whether the AMP tables list `element`.
+fn is_known_element(element: &str) -> bool {
+    GLOBAL_ATTRIBUTE_ELEMENTS.contains(&element)
+        || ELEMENT_ATTRIBUTES.iter().any(|(tag, _)| *tag == element)
+}
+
+/// Whether AMP allows the attribute `name` on `element`.
+fn is_allowed_attr(element: &str, name: &str) -> bool {
+    if GLOBAL_ATTRIBUTE_PREFIXES
+        .iter()
+        .any(|prefix| name.to_ascii_lowercase().starts_with(prefix))
+    {
+        return true;
+    }
+
+    let name = normalize_attr_name(name);
+    GLOBAL_ATTRIBUTES.contains(&&*name)
+        || ELEMENT_ATTRIBUTES
+            .iter()
+            .any(|(tag, attrs)| *tag == element && attrs.contains(&&*name))
+}
+
+/// Reports attributes that aren't whitelisted for `element`, and event
+/// handlers in AMP-only pages. Custom AMP components define their own
+/// attributes and are skipped, as are attributes of elements the tables don't
+/// know.
+fn validate_attrs(element: &str, attrs: &[JSXAttrOrSpread], amp: Amp) {
+    if element.starts_with("amp-") {
+        return;
+    }
+    let is_known = is_known_element(element);
+
+    for attr in attrs {
+        let attr = match attr {
+            JSXAttrOrSpread::JSXAttr(attr) => attr,
+            _ => continue,
+        };
+        let name = attr_name(attr);
+        let message = if is_event_handler(&name) {
+            // Hybrid pages are hydrated, so handlers run on the non-AMP version.
+            if amp != Amp::Only {
+                continue;
+            }
+            format!("The `{}` handler is never called in AMP-only pages.", name)
+        } else if !is_known || is_allowed_attr(element, &name) {
+            continue;
+        } else {
+            format!(
+                "The `{}` attribute is not allowed on `<{}>` in AMP pages.",
+                name, element
+            )
+        };
+
+        HANDLER.with(|handler| handler.struct_span_err(attr.span, &message).emit());
+    }
+}
@@ -36,6 +36,7 @@ use fxhash::FxHashSet;
 
 use serde::Deserialize;
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::rc::Rc;
 use std::{path::PathBuf, sync::Arc};
 
@@ -75,6 +76,14 @@ pub struct TransformOptions {
     #[serde(default)]
     pub disable_page_config: bool,
 
+    /// Derive the marker of AMP-only pages from the file instead of the
+    /// build time.
+    #[serde(default)]
+    pub deterministic_amp_marker: bool,
+
+    #[serde(default)]
+    pub server_only_exports: Option<next_ssg::ServerOnlyExportsConfig>,
+
     #[serde(default)]
     pub pages_dir: Option<PathBuf>,
 
@@ -96,6 +105,9 @@ pub struct TransformOptions {
     #[serde(default)]
     pub styled_jsx: bool,
 
+    #[serde(default)]
+    pub next_dynamic: Option<next_dynamic::Config>,
+
     #[serde(default)]
     pub styled_components: Option<next_binding::swc::custom_transform::styled_components::Config>,
 
@@ -131,12 +143,17 @@ pub struct TransformOptions {
     pub server_actions: Option<server_actions::Config>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn custom_before_pass<'a, C: Comments + 'a>(
     cm: Arc<SourceMap>,
     file: Arc<SourceFile>,
     opts: &'a TransformOptions,
     comments: C,
     eliminated_packages: Rc<RefCell<FxHashSet<String>>>,
+    loadable_manifest: Rc<RefCell<Vec<next_dynamic::LoadableManifestEntry>>>,
+    page_config: Rc<RefCell<Option<page_config::PageConfigExport>>>,
+    font_queries: Rc<RefCell<BTreeMap<String, String>>>,
+    font_manifest: Rc<RefCell<Vec<next_font_loaders::FontManifestEntry>>>,
 ) -> impl Fold + 'a
 where
     C: Clone,
@@ -193,6 +210,10 @@ where
             next_ssg::next_ssg(eliminated_packages),
             !opts.disable_next_ssg
         ),
+        match &opts.server_only_exports {
+            Some(config) => Either::Left(next_ssg::server_only_exports(config.clone())),
+            None => Either::Right(noop()),
+        },
         amp_attributes::amp_attributes(),
         next_dynamic::next_dynamic(
             opts.is_development,
@@ -205,10 +226,26 @@ where
                 _ => false,
             },
             file.name.clone(),
-            opts.pages_dir.clone()
+            opts.pages_dir.clone(),
+            opts.app_dir.clone(),
+            opts.next_dynamic.clone().unwrap_or_default(),
+            loadable_manifest
         ),
         Optional::new(
-            page_config::page_config(opts.is_development, opts.is_page_file),
+            page_config::page_config(
+                opts.is_development,
+                opts.is_page_file,
+                if opts.deterministic_amp_marker {
+                    Some(page_config::content_hash_marker(
+                        &file.name,
+                        opts.pages_dir.as_deref(),
+                        &file.src,
+                    ))
+                } else {
+                    None
+                },
+                page_config
+            ),
             !opts.disable_page_config
         ),
         relay_plugin,
@@ -257,7 +294,11 @@ where
             None => Either::Right(noop()),
         },
         match &opts.font_loaders {
-            Some(config) => Either::Left(next_font_loaders::next_font_loaders(config.clone())),
+            Some(config) => Either::Left(next_font_loaders::next_font_loaders(
+                config.clone(),
+                font_queries,
+                font_manifest
+            )),
             None => Either::Right(noop()),
         },
         match &opts.server_actions {
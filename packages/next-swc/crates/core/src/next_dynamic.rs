@@ -1,48 +1,234 @@
-use std::path::{Path, PathBuf};
+use std::{
+    cell::RefCell,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
 use pathdiff::diff_paths;
+use serde::Deserialize;
 
 use next_binding::swc::core::{
-    common::{errors::HANDLER, FileName, DUMMY_SP},
+    common::{errors::HANDLER, FileName, Span, Spanned, DUMMY_SP},
     ecma::ast::{
-        ArrayLit, ArrowExpr, BinExpr, BinaryOp, BlockStmtOrExpr, Bool, CallExpr, Callee, Expr,
-        ExprOrSpread, Id, Ident, ImportDecl, ImportSpecifier, KeyValueProp, Lit, MemberExpr,
-        MemberProp, Null, ObjectLit, Prop, PropName, PropOrSpread, Str, Tpl,
+        ArrayLit, ArrowExpr, BinExpr, BinaryOp, BlockStmtOrExpr, Bool, CallExpr, Callee,
+        ComputedPropName, Expr, ExprOrSpread, GetterProp, Id, Ident, ImportDecl,
+        ImportNamedSpecifier, ImportSpecifier, KeyValueProp, Lit, MemberExpr, MemberProp,
+        MethodProp, ModuleExportName, Null, ObjectLit, Prop, PropName, PropOrSpread, SetterProp,
+        SpreadElement, Str, Tpl,
     },
-    ecma::atoms::js_word,
+    ecma::atoms::{js_word, JsWord},
     ecma::utils::ExprFactory,
     ecma::visit::{Fold, FoldWith},
 };
 
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Config {
+    /// Modules whose exports should be treated as `next/dynamic`.
+    #[serde(default)]
+    pub wrappers: Vec<DynamicWrapper>,
+    /// How production client builds reference dynamically imported modules.
+    #[serde(default)]
+    pub output: OutputMode,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum OutputMode {
+    /// `webpack: () => [require.resolveWeak(specifier)]`
+    #[default]
+    Webpack,
+    /// `modules: [helper(specifier)]`, where `helper` is provided by the
+    /// bundler.
+    Neutral {
+        #[serde(default = "default_module_id_helper")]
+        helper: JsWord,
+    },
+}
+
+fn default_module_id_helper() -> JsWord {
+    "__next_module_id__".into()
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DynamicWrapper {
+    pub module: JsWord,
+    /// Names of the exports of `module` which are `next/dynamic`.
+    pub exports: Vec<JsWord>,
+}
+
+/// A `next/dynamic` call, as recorded for the loadable manifest.
+#[derive(Clone, Debug)]
+pub struct LoadableManifestEntry {
+    /// The dynamically imported module. Expressions of template literals are
+    /// replaced by `*`.
+    pub specifier: String,
+    /// The `ssr` option, if it is a boolean literal.
+    pub ssr: Option<bool>,
+    /// Whether a `loading` option is passed.
+    pub has_loading: bool,
+    pub span: Span,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn next_dynamic(
     is_development: bool,
     is_server: bool,
     is_server_components: bool,
     filename: FileName,
     pages_dir: Option<PathBuf>,
+    app_dir: Option<PathBuf>,
+    config: Config,
+    loadable_manifest: Rc<RefCell<Vec<LoadableManifestEntry>>>,
 ) -> impl Fold {
+    let is_in_app_dir = match (&app_dir, &filename) {
+        (Some(app_dir), FileName::Real(path)) => path.starts_with(app_dir),
+        _ => false,
+    };
+
     NextDynamicPatcher {
         is_development,
         is_server,
         is_server_components,
         pages_dir,
+        app_dir,
+        is_in_app_dir,
         filename,
+        config,
+        loadable_manifest,
         dynamic_bindings: vec![],
+        dynamic_namespaces: vec![],
         is_next_dynamic_first_arg: false,
         dynamically_imported_specifier: None,
     }
 }
 
+#[derive(Debug)]
+enum ImportedSpecifier {
+    /// A string literal, or a template literal without expressions.
+    Static(String),
+    /// A template literal with expressions and a relative prefix, which the
+    /// bundler resolves as a context module.
+    Context(Tpl),
+}
+
+impl ImportedSpecifier {
+    /// The specifier recorded in `loadableGenerated.modules`. Expressions of
+    /// a template literal are replaced by `*`.
+    fn pattern(&self) -> String {
+        match self {
+            ImportedSpecifier::Static(specifier) => specifier.clone(),
+            ImportedSpecifier::Context(Tpl { quasis, .. }) => quasis
+                .iter()
+                .map(|quasi| &*quasi.raw)
+                .collect::<Vec<_>>()
+                .join("*"),
+        }
+    }
+
+    /// The argument passed to `require.resolveWeak`.
+    fn to_expr(&self) -> Expr {
+        match self {
+            ImportedSpecifier::Static(specifier) => Expr::Lit(Lit::Str(Str {
+                value: specifier.clone().into(),
+                span: DUMMY_SP,
+                raw: None,
+            })),
+            ImportedSpecifier::Context(tpl) => Expr::Tpl(Tpl {
+                span: DUMMY_SP,
+                ..tpl.clone()
+            }),
+        }
+    }
+}
+
+fn is_relative_prefix(prefix: &str) -> bool {
+    prefix.starts_with("./") || prefix.starts_with("../")
+}
+
 #[derive(Debug)]
 struct NextDynamicPatcher {
     is_development: bool,
     is_server: bool,
     is_server_components: bool,
     pages_dir: Option<PathBuf>,
+    app_dir: Option<PathBuf>,
+    /// Whether the file is an app-router module, in which case module IDs are
+    /// relative to `app_dir`.
+    is_in_app_dir: bool,
     filename: FileName,
+    config: Config,
+    loadable_manifest: Rc<RefCell<Vec<LoadableManifestEntry>>>,
     dynamic_bindings: Vec<Id>,
+    /// Namespace imports, along with the names of their members which are
+    /// `next/dynamic`.
+    dynamic_namespaces: Vec<(Id, Vec<JsWord>)>,
     is_next_dynamic_first_arg: bool,
-    dynamically_imported_specifier: Option<String>,
+    dynamically_imported_specifier: Option<ImportedSpecifier>,
+}
+
+impl NextDynamicPatcher {
+    /// The directory module IDs are relative to.
+    fn base_dir(&self) -> Option<&Path> {
+        if self.is_in_app_dir {
+            self.app_dir.as_deref()
+        } else {
+            self.pages_dir.as_deref()
+        }
+    }
+
+    /// Returns the names of the exports of `src` which are `next/dynamic`.
+    fn dynamic_exports_of(&self, src: &JsWord) -> Option<Vec<JsWord>> {
+        if src == "next/dynamic" {
+            return Some(vec![js_word!("default")]);
+        }
+
+        self.config
+            .wrappers
+            .iter()
+            .find(|wrapper| &wrapper.module == src)
+            .map(|wrapper| wrapper.exports.clone())
+    }
+
+    /// Returns the span of `callee` if it is `next/dynamic`, either through
+    /// an imported binding or a member of a namespace import.
+    fn dynamic_callee_span(&self, callee: &Callee) -> Option<Span> {
+        let callee = match callee {
+            Callee::Expr(callee) => callee,
+            _ => return None,
+        };
+
+        match &**callee {
+            Expr::Ident(identifier) if self.dynamic_bindings.contains(&identifier.to_id()) => {
+                Some(identifier.span)
+            }
+            Expr::Member(MemberExpr { obj, prop, span }) => {
+                let namespace = match &**obj {
+                    Expr::Ident(namespace) => namespace.to_id(),
+                    _ => return None,
+                };
+                let (_, exports) = self
+                    .dynamic_namespaces
+                    .iter()
+                    .find(|(id, _)| *id == namespace)?;
+                let member = match prop {
+                    MemberProp::Ident(ident) => &ident.sym,
+                    MemberProp::Computed(ComputedPropName { expr, .. }) => match &**expr {
+                        Expr::Lit(Lit::Str(Str { value, .. })) => value,
+                        _ => return None,
+                    },
+                    _ => return None,
+                };
+
+                if exports.contains(member) {
+                    Some(*span)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
 }
 
 impl Fold for NextDynamicPatcher {
@@ -52,10 +238,30 @@ impl Fold for NextDynamicPatcher {
             ref specifiers,
             ..
         } = decl;
-        if &src.value == "next/dynamic" {
+        if let Some(exports) = self.dynamic_exports_of(&src.value) {
             for specifier in specifiers {
-                if let ImportSpecifier::Default(default_specifier) = specifier {
-                    self.dynamic_bindings.push(default_specifier.local.to_id());
+                match specifier {
+                    ImportSpecifier::Default(default_specifier) => {
+                        if exports.contains(&js_word!("default")) {
+                            self.dynamic_bindings.push(default_specifier.local.to_id());
+                        }
+                    }
+                    ImportSpecifier::Named(ImportNamedSpecifier {
+                        local, imported, ..
+                    }) => {
+                        let imported = match imported {
+                            Some(ModuleExportName::Ident(ident)) => &ident.sym,
+                            Some(ModuleExportName::Str(str)) => &str.value,
+                            None => &local.sym,
+                        };
+                        if exports.contains(imported) {
+                            self.dynamic_bindings.push(local.to_id());
+                        }
+                    }
+                    ImportSpecifier::Namespace(namespace_specifier) => {
+                        self.dynamic_namespaces
+                            .push((namespace_specifier.local.to_id(), exports.clone()));
+                    }
                 }
             }
         }
@@ -68,113 +274,129 @@ impl Fold for NextDynamicPatcher {
             if let Callee::Import(..) = &expr.callee {
                 match &*expr.args[0].expr {
                     Expr::Lit(Lit::Str(Str { value, .. })) => {
-                        self.dynamically_imported_specifier = Some(value.to_string());
+                        self.dynamically_imported_specifier =
+                            Some(ImportedSpecifier::Static(value.to_string()));
                     }
                     Expr::Tpl(Tpl { exprs, quasis, .. }) if exprs.is_empty() => {
-                        self.dynamically_imported_specifier = Some(quasis[0].raw.to_string());
+                        self.dynamically_imported_specifier =
+                            Some(ImportedSpecifier::Static(quasis[0].raw.to_string()));
+                    }
+                    // Only a relative prefix tells the bundler which directory
+                    // the context module covers; anything else, like
+                    // `${root}/component`, is left without loadableGenerated as
+                    // before.
+                    Expr::Tpl(tpl) => {
+                        if is_relative_prefix(&tpl.quasis[0].raw) {
+                            self.dynamically_imported_specifier =
+                                Some(ImportedSpecifier::Context(tpl.clone()));
+                        }
                     }
-                    _ => {}
+                    arg => HANDLER.with(|handler| {
+                        handler
+                            .struct_span_warn(
+                                arg.span(),
+                                "next/dynamic could not analyze this import, so it will not be \
+                                 preloaded.\nUse a string literal or a template literal as the \
+                                 module specifier.",
+                            )
+                            .emit()
+                    }),
                 }
             }
             return expr.fold_children_with(self);
         }
         let mut expr = expr.fold_children_with(self);
-        if let Callee::Expr(i) = &expr.callee {
-            if let Expr::Ident(identifier) = &**i {
-                if self.dynamic_bindings.contains(&identifier.to_id()) {
-                    if expr.args.is_empty() {
-                        HANDLER.with(|handler| {
-                            handler
-                                .struct_span_err(
-                                    identifier.span,
-                                    "next/dynamic requires at least one argument",
-                                )
-                                .emit()
-                        });
-                        return expr;
-                    } else if expr.args.len() > 2 {
+        if let Some(callee_span) = self.dynamic_callee_span(&expr.callee) {
+            if expr.args.is_empty() {
+                HANDLER.with(|handler| {
+                    handler
+                        .struct_span_err(callee_span, "next/dynamic requires at least one argument")
+                        .emit()
+                });
+                return expr;
+            } else if expr.args.len() > 2 {
+                HANDLER.with(|handler| {
+                    handler
+                        .struct_span_err(callee_span, "next/dynamic only accepts 2 arguments")
+                        .emit()
+                });
+                return expr;
+            }
+            if expr.args.len() == 2 {
+                match &*expr.args[1].expr {
+                    Expr::Object(_) => {}
+                    _ => {
                         HANDLER.with(|handler| {
-                            handler
-                                .struct_span_err(
-                                    identifier.span,
-                                    "next/dynamic only accepts 2 arguments",
-                                )
-                                .emit()
-                        });
+                  handler
+                      .struct_span_err(
+                          callee_span,
+                          "next/dynamic options must be an object literal.\nRead more: https://nextjs.org/docs/messages/invalid-dynamic-options-type",
+                      )
+                      .emit();
+              });
                         return expr;
                     }
-                    if expr.args.len() == 2 {
-                        match &*expr.args[1].expr {
-                            Expr::Object(_) => {}
-                            _ => {
-                                HANDLER.with(|handler| {
-                          handler
-                              .struct_span_err(
-                                  identifier.span,
-                                  "next/dynamic options must be an object literal.\nRead more: https://nextjs.org/docs/messages/invalid-dynamic-options-type",
-                              )
-                              .emit();
-                      });
-                                return expr;
-                            }
-                        }
-                    }
+                }
+            }
 
-                    self.is_next_dynamic_first_arg = true;
-                    expr.args[0].expr = expr.args[0].expr.clone().fold_with(self);
-                    self.is_next_dynamic_first_arg = false;
+            self.is_next_dynamic_first_arg = true;
+            expr.args[0].expr = expr.args[0].expr.clone().fold_with(self);
+            self.is_next_dynamic_first_arg = false;
 
-                    if self.dynamically_imported_specifier.is_none() {
-                        return expr;
-                    }
+            let specifier = match self.dynamically_imported_specifier.take() {
+                Some(specifier) => specifier,
+                None => return expr,
+            };
 
-                    // dev client or server:
-                    // loadableGenerated: {
-                    //   modules:
-                    // ["/project/src/file-being-transformed.js -> " + '../components/hello'] }
-
-                    // prod client
-                    // loadableGenerated: {
-                    //   webpack: () => [require.resolveWeak('../components/hello')],
-                    let generated = Box::new(Expr::Object(ObjectLit {
-                        span: DUMMY_SP,
-                        props: if self.is_development || self.is_server {
-                            vec![PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
-                                key: PropName::Ident(Ident::new("modules".into(), DUMMY_SP)),
-                                value: Box::new(Expr::Array(ArrayLit {
-                                    elems: vec![Some(ExprOrSpread {
-                                        expr: Box::new(Expr::Bin(BinExpr {
-                                            span: DUMMY_SP,
-                                            op: BinaryOp::Add,
-                                            left: Box::new(Expr::Lit(Lit::Str(Str {
-                                                value: format!(
-                                                    "{} -> ",
-                                                    rel_filename(
-                                                        self.pages_dir.as_deref(),
-                                                        &self.filename
-                                                    )
-                                                )
-                                                .into(),
-                                                span: DUMMY_SP,
-                                                raw: None,
-                                            }))),
-                                            right: Box::new(Expr::Lit(Lit::Str(Str {
-                                                value: self
-                                                    .dynamically_imported_specifier
-                                                    .as_ref()
-                                                    .unwrap()
-                                                    .clone()
-                                                    .into(),
-                                                span: DUMMY_SP,
-                                                raw: None,
-                                            }))),
-                                        })),
-                                        spread: None,
-                                    })],
+            // dev client or server:
+            // loadableGenerated: {
+            //   modules:
+            // ["/project/src/file-being-transformed.js -> " + '../components/hello'] }
+
+            // prod client
+            // loadableGenerated: {
+            //   webpack: () => [require.resolveWeak('../components/hello')],
+
+            // prod client, neutral output
+            // loadableGenerated: {
+            //   modules: [helper('../components/hello')] }
+
+            // Template literals with expressions are recorded as a pattern,
+            // e.g. `../locales/${lang}` becomes '../locales/*' in `modules`
+            // and is passed as-is to `require.resolveWeak`.
+            let generated = Box::new(Expr::Object(ObjectLit {
+                span: DUMMY_SP,
+                props: if self.is_development || self.is_server {
+                    vec![PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                        key: PropName::Ident(Ident::new("modules".into(), DUMMY_SP)),
+                        value: Box::new(Expr::Array(ArrayLit {
+                            elems: vec![Some(ExprOrSpread {
+                                expr: Box::new(Expr::Bin(BinExpr {
                                     span: DUMMY_SP,
+                                    op: BinaryOp::Add,
+                                    left: Box::new(Expr::Lit(Lit::Str(Str {
+                                        value: format!(
+                                            "{} -> ",
+                                            rel_filename(self.base_dir(), &self.filename)
+                                        )
+                                        .into(),
+                                        span: DUMMY_SP,
+                                        raw: None,
+                                    }))),
+                                    right: Box::new(Expr::Lit(Lit::Str(Str {
+                                        value: specifier.pattern().into(),
+                                        span: DUMMY_SP,
+                                        raw: None,
+                                    }))),
                                 })),
-                            })))]
-                        } else {
+                                spread: None,
+                            })],
+                            span: DUMMY_SP,
+                        })),
+                    })))]
+                } else {
+                    match &self.config.output {
+                        OutputMode::Webpack => {
                             vec![PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
                                 key: PropName::Ident(Ident::new("webpack".into(), DUMMY_SP)),
                                 value: Box::new(Expr::Arrow(ArrowExpr {
@@ -198,16 +420,7 @@ impl Fold for NextDynamicPatcher {
                                                     },
                                                 ))),
                                                 args: vec![ExprOrSpread {
-                                                    expr: Box::new(Expr::Lit(Lit::Str(Str {
-                                                        value: self
-                                                            .dynamically_imported_specifier
-                                                            .as_ref()
-                                                            .unwrap()
-                                                            .clone()
-                                                            .into(),
-                                                        span: DUMMY_SP,
-                                                        raw: None,
-                                                    }))),
+                                                    expr: Box::new(specifier.to_expr()),
                                                     spread: None,
                                                 }],
                                                 span: DUMMY_SP,
@@ -224,80 +437,173 @@ impl Fold for NextDynamicPatcher {
                                     type_params: None,
                                 })),
                             })))]
-                        },
-                    }));
-
-                    let mut props =
-                        vec![PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
-                            key: PropName::Ident(Ident::new("loadableGenerated".into(), DUMMY_SP)),
-                            value: generated,
-                        })))];
-
-                    let mut has_ssr_false = false;
-
-                    if expr.args.len() == 2 {
-                        if let Expr::Object(ObjectLit {
-                            props: options_props,
-                            ..
-                        }) = &*expr.args[1].expr
-                        {
-                            for prop in options_props.iter() {
-                                if let Some(KeyValueProp { key, value }) = match prop {
-                                    PropOrSpread::Prop(prop) => match &**prop {
-                                        Prop::KeyValue(key_value_prop) => Some(key_value_prop),
-                                        _ => None,
-                                    },
-                                    _ => None,
-                                } {
-                                    if let Some(Ident {
-                                        sym,
-                                        span: _,
-                                        optional: _,
-                                    }) = match key {
-                                        PropName::Ident(ident) => Some(ident),
-                                        _ => None,
-                                    } {
-                                        if sym == "ssr" {
-                                            if let Some(Lit::Bool(Bool {
-                                                value: false,
-                                                span: _,
-                                            })) = value.as_lit()
-                                            {
-                                                has_ssr_false = true
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                            props.extend(options_props.iter().cloned());
+                        }
+                        OutputMode::Neutral { helper } => {
+                            vec![PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                                key: PropName::Ident(Ident::new("modules".into(), DUMMY_SP)),
+                                value: Box::new(Expr::Array(ArrayLit {
+                                    elems: vec![Some(ExprOrSpread {
+                                        expr: Box::new(Expr::Call(CallExpr {
+                                            callee: Ident::new(helper.clone(), DUMMY_SP)
+                                                .as_callee(),
+                                            args: vec![specifier.to_expr().as_arg()],
+                                            span: DUMMY_SP,
+                                            type_args: None,
+                                        })),
+                                        spread: None,
+                                    })],
+                                    span: DUMMY_SP,
+                                })),
+                            })))]
                         }
                     }
+                },
+            }));
 
-                    if has_ssr_false && self.is_server && !self.is_server_components {
-                        expr.args[0] = Lit::Null(Null { span: DUMMY_SP }).as_arg();
-                    }
+            let mut props = vec![PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                key: PropName::Ident(Ident::new("loadableGenerated".into(), DUMMY_SP)),
+                value: generated,
+            })))];
 
-                    let second_arg = ExprOrSpread {
-                        spread: None,
-                        expr: Box::new(Expr::Object(ObjectLit {
-                            span: DUMMY_SP,
-                            props,
-                        })),
-                    };
+            let mut ssr = None;
+            let mut has_loading = false;
 
-                    if expr.args.len() == 2 {
-                        expr.args[1] = second_arg;
-                    } else {
-                        expr.args.push(second_arg)
+            if expr.args.len() == 2 {
+                if let Expr::Object(ObjectLit {
+                    props: options_props,
+                    ..
+                }) = &*expr.args[1].expr
+                {
+                    for prop in options_props.iter() {
+                        let prop = match prop {
+                            PropOrSpread::Prop(prop) => prop,
+                            PropOrSpread::Spread(SpreadElement {
+                                dot3_token,
+                                expr: spread,
+                            }) => {
+                                warn(
+                                    dot3_token.to(spread.span()),
+                                    "next/dynamic options should not be spread, as they may hide \
+                                     `ssr: false`.",
+                                );
+                                continue;
+                            }
+                        };
+                        let (key, value) = match &**prop {
+                            Prop::KeyValue(KeyValueProp { key, value }) => (key, Some(&**value)),
+                            Prop::Method(MethodProp { key, .. })
+                            | Prop::Getter(GetterProp { key, .. })
+                            | Prop::Setter(SetterProp { key, .. }) => (key, None),
+                            Prop::Shorthand(ident) => {
+                                validate_option(&ident.sym, ident.span, None);
+                                if &*ident.sym == "loading" {
+                                    has_loading = true
+                                }
+                                continue;
+                            }
+                            Prop::Assign(..) => continue,
+                        };
+                        let (sym, key_span) = match key {
+                            PropName::Ident(Ident { sym, span, .. }) => (sym, *span),
+                            PropName::Str(Str { value, span, .. }) => (value, *span),
+                            _ => {
+                                warn(
+                                    key.span(),
+                                    "next/dynamic options should not use computed or numeric keys.",
+                                );
+                                continue;
+                            }
+                        };
+                        if let Some(value) = validate_option(sym, key_span, value) {
+                            ssr = Some(value)
+                        }
+                        if sym == "loading" {
+                            has_loading = true
+                        }
                     }
-                    self.dynamically_imported_specifier = None;
+                    props.extend(options_props.iter().cloned());
                 }
             }
+
+            if ssr == Some(false) && self.is_server && !self.is_server_components {
+                expr.args[0] = Lit::Null(Null { span: DUMMY_SP }).as_arg();
+            }
+
+            self.loadable_manifest
+                .borrow_mut()
+                .push(LoadableManifestEntry {
+                    specifier: specifier.pattern(),
+                    ssr,
+                    has_loading,
+                    span: expr.span,
+                });
+
+            let second_arg = ExprOrSpread {
+                spread: None,
+                expr: Box::new(Expr::Object(ObjectLit {
+                    span: DUMMY_SP,
+                    props,
+                })),
+            };
+
+            if expr.args.len() == 2 {
+                expr.args[1] = second_arg;
+            } else {
+                expr.args.push(second_arg)
+            }
         }
         expr
     }
 }
 
+/// The options `next/dynamic` accepts.
+const OPTIONS: &[&str] = &["ssr", "loading", "suspense", "loadableGenerated"];
+
+fn warn(span: Span, msg: &str) {
+    HANDLER.with(|handler| handler.struct_span_warn(span, msg).emit());
+}
+
+/// Warns about an unknown option, or a value which cannot be the kind the
+/// option expects. `value` is `None` when it is not a plain expression, e.g.
+/// a method or a shorthand property. Returns the value of `ssr` if it is a
+/// boolean literal.
+fn validate_option(sym: &JsWord, span: Span, value: Option<&Expr>) -> Option<bool> {
+    if !OPTIONS.contains(&&**sym) {
+        warn(
+            span,
+            &format!(
+                "Unknown next/dynamic option `{}`.\nSupported options are {}.",
+                sym,
+                OPTIONS
+                    .iter()
+                    .map(|option| format!("`{}`", option))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        );
+        return None;
+    }
+
+    match (&**sym, value) {
+        ("ssr", Some(Expr::Lit(Lit::Bool(Bool { value, .. })))) => return Some(*value),
+        ("ssr", value) => warn(
+            value.map_or(span, |value| value.span()),
+            "next/dynamic `ssr` option must be a boolean literal.",
+        ),
+        ("suspense", Some(Expr::Lit(lit))) if !matches!(lit, Lit::Bool(..)) => warn(
+            lit.span(),
+            "next/dynamic `suspense` option must be a boolean.",
+        ),
+        ("loading", Some(Expr::Lit(lit))) if !matches!(lit, Lit::Null(..)) => warn(
+            lit.span(),
+            "next/dynamic `loading` option must be a component.",
+        ),
+        _ => {}
+    }
+
+    None
+}
+
 fn rel_filename(base: Option<&Path>, file: &FileName) -> String {
     let base = match base {
         Some(v) => v,
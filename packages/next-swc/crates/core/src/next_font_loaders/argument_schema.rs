@@ -0,0 +1,214 @@
+use fxhash::FxHashMap;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// A JSON-schema-like description of a font loader argument.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct Schema {
+    #[serde(default, rename = "type")]
+    pub types: Option<SchemaTypes>,
+    #[serde(default, rename = "enum")]
+    pub allowed_values: Option<Vec<Value>>,
+    #[serde(default)]
+    pub properties: FxHashMap<String, Schema>,
+    #[serde(default)]
+    pub required: Vec<String>,
+    /// Whether properties not listed in `properties` are allowed. Defaults to
+    /// `true`, like in JSON schema.
+    #[serde(default)]
+    pub additional_properties: Option<bool>,
+    #[serde(default)]
+    pub items: Option<Box<Schema>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum SchemaTypes {
+    One(SchemaType),
+    Many(Vec<SchemaType>),
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SchemaType {
+    String,
+    Number,
+    Boolean,
+    Null,
+    Array,
+    Object,
+}
+
+impl SchemaTypes {
+    fn as_slice(&self) -> &[SchemaType] {
+        match self {
+            SchemaTypes::One(ty) => std::slice::from_ref(ty),
+            SchemaTypes::Many(types) => types,
+        }
+    }
+}
+
+impl SchemaType {
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::String(..) => SchemaType::String,
+            Value::Number(..) => SchemaType::Number,
+            Value::Bool(..) => SchemaType::Boolean,
+            Value::Null => SchemaType::Null,
+            Value::Array(..) => SchemaType::Array,
+            Value::Object(..) => SchemaType::Object,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            SchemaType::String => "string",
+            SchemaType::Number => "number",
+            SchemaType::Boolean => "boolean",
+            SchemaType::Null => "null",
+            SchemaType::Array => "array",
+            SchemaType::Object => "object",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug)]
+pub enum SchemaErrorKind {
+    InvalidType(Vec<SchemaType>),
+    InvalidValue(Vec<Value>),
+    MissingProperty(String),
+    UnknownProperty,
+}
+
+/// A value that doesn't match its schema, located by its path in the
+/// argument.
+#[derive(Debug)]
+pub struct SchemaError {
+    pub path: Vec<PathSegment>,
+    pub kind: SchemaErrorKind,
+}
+
+impl SchemaError {
+    /// `name` is how the path of the error is displayed.
+    pub fn message(&self, name: &str) -> String {
+        match &self.kind {
+            SchemaErrorKind::InvalidType(types) => format!(
+                "Invalid value found for `{}`, expected {}.",
+                name,
+                types
+                    .iter()
+                    .map(|ty| ty.name())
+                    .collect::<Vec<_>>()
+                    .join(" or ")
+            ),
+            SchemaErrorKind::InvalidValue(allowed_values) => format!(
+                "Invalid value found for `{}`, expected one of {}.",
+                name,
+                allowed_values
+                    .iter()
+                    .map(|value| value.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            SchemaErrorKind::MissingProperty(key) => {
+                if self.path.is_empty() {
+                    format!("Missing required option `{}`.", key)
+                } else {
+                    format!("Missing required option `{}` in `{}`.", key, name)
+                }
+            }
+            SchemaErrorKind::UnknownProperty => format!("Unknown option `{}`.", name),
+        }
+    }
+}
+
+/// Displays `path` the way it would be written in JavaScript, e.g.
+/// `axes[0]`.
+pub fn display_path(path: &[PathSegment]) -> String {
+    let mut name = String::new();
+    for segment in path {
+        match segment {
+            PathSegment::Key(key) => {
+                if !name.is_empty() {
+                    name.push('.');
+                }
+                name.push_str(key);
+            }
+            PathSegment::Index(index) => name.push_str(&format!("[{}]", index)),
+        }
+    }
+    name
+}
+
+/// Collects the parts of `value` that don't match `schema`.
+pub fn validate(
+    value: &Value,
+    schema: &Schema,
+    path: &mut Vec<PathSegment>,
+    errors: &mut Vec<SchemaError>,
+) {
+    if let Some(types) = &schema.types {
+        if !types.as_slice().contains(&SchemaType::of(value)) {
+            errors.push(SchemaError {
+                path: path.clone(),
+                kind: SchemaErrorKind::InvalidType(types.as_slice().to_vec()),
+            });
+            return;
+        }
+    }
+
+    if let Some(allowed_values) = &schema.allowed_values {
+        if !allowed_values.contains(value) {
+            errors.push(SchemaError {
+                path: path.clone(),
+                kind: SchemaErrorKind::InvalidValue(allowed_values.clone()),
+            });
+            return;
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                path.push(PathSegment::Key(key.clone()));
+                match schema.properties.get(key) {
+                    Some(property_schema) => validate(value, property_schema, path, errors),
+                    None if schema.additional_properties == Some(false) => {
+                        errors.push(SchemaError {
+                            path: path.clone(),
+                            kind: SchemaErrorKind::UnknownProperty,
+                        })
+                    }
+                    None => {}
+                }
+                path.pop();
+            }
+
+            for key in &schema.required {
+                if !object.contains_key(key) {
+                    errors.push(SchemaError {
+                        path: path.clone(),
+                        kind: SchemaErrorKind::MissingProperty(key.clone()),
+                    });
+                }
+            }
+        }
+        Value::Array(elements) => {
+            if let Some(items) = &schema.items {
+                for (index, element) in elements.iter().enumerate() {
+                    path.push(PathSegment::Index(index));
+                    validate(element, items, path, errors);
+                    path.pop();
+                }
+            }
+        }
+        _ => {}
+    }
+}
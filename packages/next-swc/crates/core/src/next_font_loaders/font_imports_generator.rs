@@ -1,13 +1,34 @@
+use std::{cell::RefCell, collections::BTreeMap};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use fxhash::{FxHashMap, FxHashSet};
+use hex::encode as hex_encode;
 use next_binding::swc::core::common::errors::HANDLER;
-use next_binding::swc::core::common::{Spanned, DUMMY_SP};
+use next_binding::swc::core::common::{Span, Spanned, DUMMY_SP};
 use next_binding::swc::core::ecma::ast::*;
 use next_binding::swc::core::ecma::atoms::JsWord;
-use next_binding::swc::core::ecma::visit::{noop_visit_type, Visit};
+use next_binding::swc::core::ecma::utils::{private_ident, quote_ident};
+use next_binding::swc::core::ecma::visit::{noop_visit_type, Visit, VisitWith};
 use serde_json::Value;
+use sha1::{Digest, Sha1};
+
+use super::{
+    argument_schema::{display_path, validate, PathSegment, Schema, SchemaErrorKind},
+    FontFunction, FontManifestEntry, QueryEncoding,
+};
 
 pub struct FontImportsGenerator<'a> {
     pub state: &'a mut super::State,
     pub relative_path: &'a str,
+    pub query_encoding: QueryEncoding,
+    pub argument_schemas: &'a FxHashMap<JsWord, FxHashMap<JsWord, Vec<Schema>>>,
+    pub font_queries: &'a RefCell<BTreeMap<String, String>>,
+    pub font_manifest: &'a RefCell<Vec<FontManifestEntry>>,
+    /// The `const`s being evaluated, to reject cyclic references.
+    pub resolving_consts: Vec<Id>,
+    /// The bindings of the font manifest entries, by index, to mark the
+    /// entries exported later on.
+    pub manifest_bindings: Vec<(usize, Id)>,
 }
 
 impl<'a> FontImportsGenerator<'a> {
@@ -15,10 +36,11 @@ impl<'a> FontImportsGenerator<'a> {
         &mut self,
         call_expr: &CallExpr,
         variable_name: &Result<Ident, &Pat>,
-    ) -> Option<ImportDecl> {
+    ) -> Option<(ImportDecl, FontManifestEntry)> {
         if let Callee::Expr(callee_expr) = &call_expr.callee {
             if let Expr::Ident(ident) = &**callee_expr {
-                if let Some(font_function) = self.state.font_functions.get(&ident.to_id()) {
+                if let Some(font_function) = self.state.font_functions.get(&ident.to_id()).cloned()
+                {
                     self.state
                         .font_functions_in_allowed_scope
                         .insert(ident.span.lo);
@@ -35,11 +57,26 @@ impl<'a> FontImportsGenerator<'a> {
                                 });
                             }
 
-                            expr_to_json(&expr_or_spread.expr)
+                            self.expr_to_json(&expr_or_spread.expr)
                         })
                         .collect();
 
                     if let Ok(json) = json {
+                        self.validate_arguments(call_expr, ident, &font_function, &json);
+
+                        let manifest_entry = FontManifestEntry {
+                            loader: font_function.loader.to_string(),
+                            function_name: font_function
+                                .function_name
+                                .as_ref()
+                                .map(|f| f.to_string()),
+                            arguments: json.clone(),
+                            variable_name: variable_name
+                                .as_ref()
+                                .map(|ident| ident.sym.to_string())
+                                .unwrap_or_default(),
+                            exported: false,
+                        };
                         let function_name = match &font_function.function_name {
                             Some(function) => String::from(&**function),
                             None => String::new(),
@@ -61,11 +98,12 @@ impl<'a> FontImportsGenerator<'a> {
 
                         let query_json = Value::Object(query_json_values);
 
-                        return Some(ImportDecl {
+                        let import_decl = ImportDecl {
                             src: Box::new(Str {
                                 value: JsWord::from(format!(
                                     "{}/target.css?{}",
-                                    font_function.loader, query_json
+                                    font_function.loader,
+                                    self.encode_query(query_json.to_string())
                                 )),
                                 raw: None,
                                 span: DUMMY_SP,
@@ -74,7 +112,8 @@ impl<'a> FontImportsGenerator<'a> {
                             type_only: false,
                             asserts: None,
                             span: DUMMY_SP,
-                        });
+                        };
+                        return Some((import_decl, manifest_entry));
                     }
                 }
             }
@@ -83,7 +122,7 @@ impl<'a> FontImportsGenerator<'a> {
         None
     }
 
-    fn check_var_decl(&mut self, var_decl: &VarDecl) -> Option<Ident> {
+    fn check_var_decl(&mut self, var_decl: &VarDecl, exported: bool) -> Option<Ident> {
         if let Some(decl) = var_decl.decls.get(0) {
             let ident = match &decl.name {
                 Pat::Ident(ident) => Ok(ident.id.clone()),
@@ -93,7 +132,7 @@ impl<'a> FontImportsGenerator<'a> {
                 if let Expr::Call(call_expr) = &**expr {
                     let import_decl = self.check_call_expr(call_expr, &ident);
 
-                    if let Some(mut import_decl) = import_decl {
+                    if let Some((mut import_decl, manifest_entry)) = import_decl {
                         match var_decl.kind {
                             VarDeclKind::Const => {}
                             _ => {
@@ -119,6 +158,13 @@ impl<'a> FontImportsGenerator<'a> {
                                 self.state
                                     .font_imports
                                     .push(ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)));
+                                let mut font_manifest = self.font_manifest.borrow_mut();
+                                self.manifest_bindings
+                                    .push((font_manifest.len(), ident.to_id()));
+                                font_manifest.push(FontManifestEntry {
+                                    exported,
+                                    ..manifest_entry
+                                });
 
                                 return Some(ident);
                             }
@@ -144,16 +190,68 @@ impl<'a> FontImportsGenerator<'a> {
 impl<'a> Visit for FontImportsGenerator<'a> {
     noop_visit_type!();
 
+    fn visit_module_items(&mut self, items: &[ModuleItem]) {
+        items.visit_children_with(self);
+
+        // Fonts can also be exported after their declaration, e.g. with
+        // `export { inter as default }`.
+        let exported: FxHashSet<Id> = items
+            .iter()
+            .flat_map(exported_locals)
+            .map(|ident| ident.to_id())
+            .collect();
+        let mut font_manifest = self.font_manifest.borrow_mut();
+        for (index, id) in &self.manifest_bindings {
+            if exported.contains(id) {
+                font_manifest[*index].exported = true;
+            }
+        }
+    }
+
     fn visit_module_item(&mut self, item: &ModuleItem) {
         match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(ExportDefaultExpr {
+                expr: box Expr::Call(call_expr),
+                span,
+            })) => {
+                let ident = private_ident!("_default");
+                // `_default` is only the local binding, the loaders and the
+                // manifest see the name of the export.
+                if let Some((mut import_decl, manifest_entry)) =
+                    self.check_call_expr(call_expr, &Ok(quote_ident!("default")))
+                {
+                    self.state.removeable_module_items.insert(span.lo);
+
+                    import_decl.specifiers =
+                        vec![ImportSpecifier::Default(ImportDefaultSpecifier {
+                            span: DUMMY_SP,
+                            local: ident.clone(),
+                        })];
+                    self.state
+                        .font_imports
+                        .push(ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)));
+                    self.state.font_exports.push(ModuleItem::ModuleDecl(
+                        ModuleDecl::ExportDefaultExpr(ExportDefaultExpr {
+                            span: DUMMY_SP,
+                            expr: Box::new(Expr::Ident(ident)),
+                        }),
+                    ));
+                    self.font_manifest.borrow_mut().push(FontManifestEntry {
+                        exported: true,
+                        ..manifest_entry
+                    });
+                }
+            }
             ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl))) => {
-                if self.check_var_decl(var_decl).is_some() {
+                if self.check_var_decl(var_decl, false).is_some() {
                     self.state.removeable_module_items.insert(var_decl.span.lo);
+                } else {
+                    self.record_consts(var_decl);
                 }
             }
             ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
                 if let Decl::Var(var_decl) = &export_decl.decl {
-                    if let Some(ident) = self.check_var_decl(var_decl) {
+                    if let Some(ident) = self.check_var_decl(var_decl, true) {
                         self.state
                             .removeable_module_items
                             .insert(export_decl.span.lo);
@@ -172,6 +270,8 @@ impl<'a> Visit for FontImportsGenerator<'a> {
                                 asserts: None,
                             }),
                         ));
+                    } else {
+                        self.record_consts(var_decl);
                     }
                 }
             }
@@ -180,90 +280,368 @@ impl<'a> Visit for FontImportsGenerator<'a> {
     }
 }
 
-fn object_lit_to_json(object_lit: &ObjectLit) -> Value {
-    let mut values = serde_json::Map::new();
-    for prop in &object_lit.props {
-        match prop {
-            PropOrSpread::Prop(prop) => match &**prop {
-                Prop::KeyValue(key_val) => {
-                    let key = match &key_val.key {
-                        PropName::Ident(ident) => Ok(String::from(&*ident.sym)),
-                        key => {
-                            HANDLER.with(|handler| {
-                                handler
-                                    .struct_span_err(key.span(), "Unexpected object key type")
-                                    .emit()
-                            });
-                            Err(())
+impl<'a> FontImportsGenerator<'a> {
+    fn object_lit_to_json(&mut self, object_lit: &ObjectLit) -> Value {
+        let mut values = serde_json::Map::new();
+        for prop in &object_lit.props {
+            match prop {
+                PropOrSpread::Prop(prop) => match &**prop {
+                    Prop::KeyValue(key_val) => {
+                        let key = match &key_val.key {
+                            PropName::Ident(ident) => Ok(String::from(&*ident.sym)),
+                            PropName::Str(str) => Ok(String::from(&*str.value)),
+                            PropName::Num(Number { value, .. }) => Ok(number_to_key(*value)),
+                            key => {
+                                HANDLER.with(|handler| {
+                                    handler
+                                        .struct_span_err(key.span(), "Unexpected object key type")
+                                        .emit()
+                                });
+                                Err(())
+                            }
+                        };
+                        let val = self.expr_to_json(&key_val.value);
+                        if let (Ok(key), Ok(val)) = (key, val) {
+                            values.insert(key, val);
                         }
-                    };
-                    let val = expr_to_json(&key_val.value);
-                    if let (Ok(key), Ok(val)) = (key, val) {
-                        values.insert(key, val);
                     }
-                }
-                key => HANDLER.with(|handler| {
-                    handler.struct_span_err(key.span(), "Unexpected key").emit();
+                    Prop::Shorthand(ident) => {
+                        if let Ok(val) = self.const_to_json(ident) {
+                            values.insert(String::from(&*ident.sym), val);
+                        }
+                    }
+                    key => HANDLER.with(|handler| {
+                        handler.struct_span_err(key.span(), "Unexpected key").emit();
+                    }),
+                },
+                PropOrSpread::Spread(spread_span) => HANDLER.with(|handler| {
+                    handler
+                        .struct_span_err(spread_span.dot3_token, "Unexpected spread")
+                        .emit();
                 }),
+            }
+        }
+
+        Value::Object(values)
+    }
+
+    fn expr_to_json(&mut self, expr: &Expr) -> Result<Value, ()> {
+        match expr {
+            Expr::Lit(Lit::Str(str)) => Ok(Value::String(String::from(&*str.value))),
+            Expr::Lit(Lit::Bool(Bool { value, .. })) => Ok(Value::Bool(*value)),
+            Expr::Lit(Lit::Num(Number { value, span, .. })) => number_to_json(*value, *span),
+            Expr::Lit(Lit::Null(..)) => Ok(Value::Null),
+            Expr::Lit(Lit::BigInt(BigInt { span, .. })) => {
+                HANDLER.with(|handler| {
+                    handler
+                        .struct_span_err(*span, "Font loaders don't accept BigInt values")
+                        .emit()
+                });
+                Err(())
+            }
+            Expr::Lit(Lit::Regex(Regex { span, .. })) => {
+                HANDLER.with(|handler| {
+                    handler
+                        .struct_span_err(*span, "Font loaders don't accept regular expressions")
+                        .emit()
+                });
+                Err(())
+            }
+            Expr::Unary(UnaryExpr {
+                op: op!(unary, "-"),
+                arg,
+                span,
+            }) => match self.expr_to_json(arg)? {
+                Value::Number(number) => {
+                    number_to_json(-number.as_f64().unwrap_or_default(), *span)
+                }
+                _ => {
+                    HANDLER.with(|handler| {
+                        handler
+                            .struct_span_err(
+                                *span,
+                                "Font loader values must be explicitly written literals.",
+                            )
+                            .emit()
+                    });
+                    Err(())
+                }
             },
-            PropOrSpread::Spread(spread_span) => HANDLER.with(|handler| {
+            Expr::Tpl(Tpl { exprs, quasis, .. }) if exprs.is_empty() => Ok(Value::String(
+                quasis
+                    .first()
+                    .map(|quasi| String::from(&**quasi.cooked.as_ref().unwrap_or(&quasi.raw)))
+                    .unwrap_or_default(),
+            )),
+            Expr::Ident(ident) => self.const_to_json(ident),
+            Expr::Object(object_lit) => Ok(self.object_lit_to_json(object_lit)),
+            Expr::Array(ArrayLit {
+                elems,
+                span: array_span,
+                ..
+            }) => {
+                let elements: Result<Vec<Value>, ()> = elems
+                    .iter()
+                    .map(|e| {
+                        if let Some(expr) = e {
+                            match expr.spread {
+                                Some(spread_span) => HANDLER.with(|handler| {
+                                    handler
+                                        .struct_span_err(spread_span, "Unexpected spread")
+                                        .emit();
+                                    Err(())
+                                }),
+                                None => self.expr_to_json(&expr.expr),
+                            }
+                        } else {
+                            HANDLER.with(|handler| {
+                                handler
+                                    .struct_span_err(*array_span, "Unexpected empty value in array")
+                                    .emit();
+                                Err(())
+                            })
+                        }
+                    })
+                    .collect();
+
+                elements.map(Value::Array)
+            }
+            lit => HANDLER.with(|handler| {
                 handler
-                    .struct_span_err(spread_span.dot3_token, "Unexpected spread")
+                    .struct_span_err(
+                        lit.span(),
+                        "Font loader values must be explicitly written literals.",
+                    )
                     .emit();
+                Err(())
             }),
         }
     }
 
-    Value::Object(values)
-}
+    fn encode_query(&self, query_json: String) -> String {
+        match self.query_encoding {
+            QueryEncoding::Json => query_json,
+            QueryEncoding::UrlEncoded => urlencoding::encode(&query_json).into_owned(),
+            QueryEncoding::Base64Url => URL_SAFE_NO_PAD.encode(&query_json),
+            QueryEncoding::Hash => {
+                let hash = hex_encode(Sha1::digest(query_json.as_bytes()));
+                self.font_queries
+                    .borrow_mut()
+                    .insert(hash.clone(), query_json);
+                hash
+            }
+        }
+    }
 
-fn expr_to_json(expr: &Expr) -> Result<Value, ()> {
-    match expr {
-        Expr::Lit(Lit::Str(str)) => Ok(Value::String(String::from(&*str.value))),
-        Expr::Lit(Lit::Bool(Bool { value, .. })) => Ok(Value::Bool(*value)),
-        Expr::Lit(Lit::Num(Number { value, .. })) => {
-            Ok(Value::Number(serde_json::Number::from_f64(*value).unwrap()))
+    /// Reports the arguments that don't match the schema configured for the
+    /// font function.
+    fn validate_arguments(
+        &self,
+        call_expr: &CallExpr,
+        callee: &Ident,
+        font_function: &FontFunction,
+        arguments: &[Value],
+    ) {
+        let schemas = match self.argument_schemas.get(&font_function.loader) {
+            Some(functions) => {
+                let function_name = font_function
+                    .function_name
+                    .clone()
+                    .unwrap_or_else(|| JsWord::from("default"));
+                match functions
+                    .get(&function_name)
+                    .or_else(|| functions.get(&JsWord::from("*")))
+                {
+                    Some(schemas) => schemas,
+                    None => return,
+                }
+            }
+            None => return,
+        };
+
+        for (index, (arg, value)) in call_expr.args.iter().zip(arguments).enumerate() {
+            let schema = match schemas.get(index) {
+                Some(schema) => schema,
+                None => {
+                    HANDLER.with(|handler| {
+                        handler
+                            .struct_span_err(
+                                arg.expr.span(),
+                                &format!("Too many arguments passed to `{}`", callee.sym),
+                            )
+                            .emit()
+                    });
+                    return;
+                }
+            };
+
+            let mut errors = vec![];
+            validate(value, schema, &mut vec![], &mut errors);
+            for error in errors {
+                let path = display_path(&error.path);
+                let name = if index == 0 && !path.is_empty() {
+                    path
+                } else if path.is_empty() || path.starts_with('[') {
+                    format!("arguments[{}]{}", index, path)
+                } else {
+                    format!("arguments[{}].{}", index, path)
+                };
+                let span = self.span_at(
+                    &arg.expr,
+                    &error.path,
+                    matches!(error.kind, SchemaErrorKind::UnknownProperty),
+                );
+                HANDLER.with(|handler| handler.struct_span_err(span, &error.message(&name)).emit());
+            }
         }
-        Expr::Object(object_lit) => Ok(object_lit_to_json(object_lit)),
-        Expr::Array(ArrayLit {
-            elems,
-            span: array_span,
-            ..
-        }) => {
-            let elements: Result<Vec<Value>, ()> = elems
-                .iter()
-                .map(|e| {
-                    if let Some(expr) = e {
-                        match expr.spread {
-                            Some(spread_span) => HANDLER.with(|handler| {
-                                handler
-                                    .struct_span_err(spread_span, "Unexpected spread")
-                                    .emit();
-                                Err(())
-                            }),
-                            None => expr_to_json(&expr.expr),
+    }
+
+    /// Finds the expression at `path` in a font loader argument, or the
+    /// whole property if `property` is set.
+    fn span_at(&self, expr: &Expr, path: &[PathSegment], property: bool) -> Span {
+        let (segment, rest) = match path.split_first() {
+            Some(split) => split,
+            None => return expr.span(),
+        };
+        let expr = match expr {
+            Expr::Ident(ident) => match self.state.const_bindings.get(&ident.to_id()) {
+                Some(init) => &**init,
+                None => expr,
+            },
+            _ => expr,
+        };
+
+        match (segment, expr) {
+            (PathSegment::Index(index), Expr::Array(ArrayLit { elems, .. })) => {
+                match elems.get(*index) {
+                    Some(Some(elem)) => self.span_at(&elem.expr, rest, property),
+                    _ => expr.span(),
+                }
+            }
+            (PathSegment::Key(key), Expr::Object(object_lit)) => {
+                for prop in object_lit.props.iter().rev() {
+                    let prop = match prop {
+                        PropOrSpread::Prop(prop) => prop,
+                        _ => continue,
+                    };
+                    match &**prop {
+                        Prop::KeyValue(key_val) if prop_name_is(&key_val.key, key) => {
+                            return if rest.is_empty() && property {
+                                prop.span()
+                            } else {
+                                self.span_at(&key_val.value, rest, property)
+                            };
                         }
-                    } else {
-                        HANDLER.with(|handler| {
-                            handler
-                                .struct_span_err(*array_span, "Unexpected empty value in array")
-                                .emit();
-                            Err(())
-                        })
+                        Prop::Shorthand(ident) if &*ident.sym == key => {
+                            return if rest.is_empty() {
+                                ident.span
+                            } else {
+                                self.span_at(&Expr::Ident(ident.clone()), rest, property)
+                            };
+                        }
+                        _ => {}
                     }
-                })
-                .collect();
+                }
+                expr.span()
+            }
+            _ => expr.span(),
+        }
+    }
+
+    /// Evaluates a reference to a module-scope `const`.
+    fn const_to_json(&mut self, ident: &Ident) -> Result<Value, ()> {
+        let id = ident.to_id();
+        let init = match self.state.const_bindings.get(&id) {
+            Some(init) if !self.resolving_consts.contains(&id) => init.clone(),
+            _ => {
+                let message = match &*ident.sym {
+                    "NaN" | "Infinity" => "Font loaders only accept finite numbers",
+                    _ => "Font loader values must be explicitly written literals.",
+                };
+                HANDLER.with(|handler| handler.struct_span_err(ident.span, message).emit());
+                return Err(());
+            }
+        };
+
+        self.resolving_consts.push(id);
+        let value = self.expr_to_json(&init);
+        self.resolving_consts.pop();
+        value
+    }
 
-            elements.map(Value::Array)
+    /// Records the initializers of `const`s so later font loader calls can
+    /// refer to them.
+    fn record_consts(&mut self, var_decl: &VarDecl) {
+        if var_decl.kind != VarDeclKind::Const {
+            return;
         }
-        lit => HANDLER.with(|handler| {
-            handler
-                .struct_span_err(
-                    lit.span(),
-                    "Font loader values must be explicitly written literals.",
-                )
-                .emit();
+        for decl in &var_decl.decls {
+            if let (Pat::Ident(ident), Some(init)) = (&decl.name, &decl.init) {
+                self.state
+                    .const_bindings
+                    .insert(ident.id.to_id(), init.clone());
+            }
+        }
+    }
+}
+
+/// JSON has no representation for `NaN` and `Infinity`, so they are reported
+/// instead of converted.
+fn number_to_json(value: f64, span: Span) -> Result<Value, ()> {
+    match serde_json::Number::from_f64(value) {
+        Some(number) => Ok(Value::Number(number)),
+        None => {
+            HANDLER.with(|handler| {
+                handler
+                    .struct_span_err(span, "Font loaders only accept finite numbers")
+                    .emit()
+            });
             Err(())
-        }),
+        }
+    }
+}
+
+/// Converts a numeric object key to the string JavaScript would use for it.
+fn number_to_key(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".into()
+    } else if value.is_infinite() {
+        if value > 0.0 { "Infinity" } else { "-Infinity" }.into()
+    } else {
+        value.to_string()
+    }
+}
+
+fn prop_name_is(prop_name: &PropName, key: &str) -> bool {
+    match prop_name {
+        PropName::Ident(ident) => &*ident.sym == key,
+        PropName::Str(str) => &*str.value == key,
+        PropName::Num(Number { value, .. }) => number_to_key(*value) == key,
+        _ => false,
+    }
+}
+
+/// The local bindings `item` exports without declaring them.
+fn exported_locals(item: &ModuleItem) -> Vec<&Ident> {
+    match item {
+        ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport {
+            specifiers,
+            src: None,
+            ..
+        })) => specifiers
+            .iter()
+            .filter_map(|specifier| match specifier {
+                ExportSpecifier::Named(ExportNamedSpecifier {
+                    orig: ModuleExportName::Ident(ident),
+                    ..
+                }) => Some(ident),
+                _ => None,
+            })
+            .collect(),
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(ExportDefaultExpr {
+            expr: box Expr::Ident(ident),
+            ..
+        })) => vec![ident],
+        _ => vec![],
     }
 }
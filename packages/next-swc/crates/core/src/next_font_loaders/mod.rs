@@ -1,14 +1,18 @@
-use fxhash::FxHashSet;
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use fxhash::{FxHashMap, FxHashSet};
 use next_binding::swc::core::{
     common::{collections::AHashMap, BytePos, Spanned},
+    ecma::{ast::ModuleItem, atoms::JsWord},
     ecma::{
-        ast::Id,
+        ast::{Expr, Id},
         visit::{as_folder, noop_visit_mut_type, Fold, VisitMut, VisitWith},
     },
-    ecma::{ast::ModuleItem, atoms::JsWord},
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
+pub mod argument_schema;
 mod find_functions_outside_module_scope;
 mod font_functions_collector;
 mod font_imports_generator;
@@ -18,18 +22,60 @@ mod font_imports_generator;
 pub struct Config {
     pub font_loaders: Vec<JsWord>,
     pub relative_file_path_from_root: JsWord,
+    #[serde(default)]
+    pub query_encoding: QueryEncoding,
+    /// Schemas of the arguments of font loader functions, by loader and then
+    /// by function name. `default` is the default export of a loader, and `*`
+    /// applies to the functions without a schema of their own.
+    #[serde(default)]
+    pub argument_schemas: FxHashMap<JsWord, FxHashMap<JsWord, Vec<argument_schema::Schema>>>,
+}
+
+/// How the arguments of a font loader call are encoded in the query of the
+/// generated import.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum QueryEncoding {
+    /// The JSON as is, e.g. `target.css?{"import":"Inter",...}`.
+    #[default]
+    Json,
+    /// The percent-encoded JSON.
+    UrlEncoded,
+    /// The JSON in unpadded base64url.
+    Base64Url,
+    /// The SHA-1 of the JSON, with the JSON itself recorded in `font_queries`.
+    Hash,
+}
+
+/// A font loader call, as returned in the font manifest of a module.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FontManifestEntry {
+    pub loader: String,
+    /// `None` for calls of the default export of the loader.
+    pub function_name: Option<String>,
+    pub arguments: Vec<Value>,
+    /// `default` for calls in `export default`.
+    pub variable_name: String,
+    pub exported: bool,
 }
 
-pub fn next_font_loaders(config: Config) -> impl Fold + VisitMut {
+pub fn next_font_loaders(
+    config: Config,
+    font_queries: Rc<RefCell<BTreeMap<String, String>>>,
+    font_manifest: Rc<RefCell<Vec<FontManifestEntry>>>,
+) -> impl Fold + VisitMut {
     as_folder(NextFontLoaders {
         config,
         state: State {
             ..Default::default()
         },
+        font_queries,
+        font_manifest,
     })
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct FontFunction {
     loader: JsWord,
     function_name: Option<JsWord>,
@@ -41,11 +87,16 @@ pub struct State {
     font_imports: Vec<ModuleItem>,
     font_exports: Vec<ModuleItem>,
     font_functions_in_allowed_scope: FxHashSet<BytePos>,
+    /// Initializers of module-scope `const`s, which font loader arguments
+    /// may refer to.
+    const_bindings: AHashMap<Id, Box<Expr>>,
 }
 
 struct NextFontLoaders {
     config: Config,
     state: State,
+    font_queries: Rc<RefCell<BTreeMap<String, String>>>,
+    font_manifest: Rc<RefCell<Vec<FontManifestEntry>>>,
 }
 
 impl VisitMut for NextFontLoaders {
@@ -64,6 +115,12 @@ impl VisitMut for NextFontLoaders {
             let mut import_generator = font_imports_generator::FontImportsGenerator {
                 state: &mut self.state,
                 relative_path: &self.config.relative_file_path_from_root,
+                query_encoding: self.config.query_encoding,
+                argument_schemas: &self.config.argument_schemas,
+                font_queries: &self.font_queries,
+                font_manifest: &self.font_manifest,
+                resolving_consts: vec![],
+                manifest_bindings: vec![],
             };
             items.visit_with(&mut import_generator);
 
@@ -1,54 +1,105 @@
-use easy_error::{bail, Error};
-use fxhash::FxHashSet;
+use fxhash::{FxHashMap, FxHashSet};
 use std::cell::RefCell;
 use std::mem::take;
 use std::rc::Rc;
 
+use serde::Deserialize;
+
 use next_binding::swc::core::{
-    common::{
-        errors::HANDLER,
-        pass::{Repeat, Repeated},
-        DUMMY_SP,
-    },
+    common::{errors::HANDLER, DUMMY_SP},
     ecma::ast::*,
-    ecma::visit::{noop_fold_type, Fold, FoldWith},
+    ecma::atoms::JsWord,
+    ecma::utils::find_pat_ids,
+    ecma::visit::{noop_fold_type, noop_visit_type, Fold, FoldWith, Visit, VisitWith},
 };
 
 static SSG_EXPORTS: &[&str; 3] = &["getStaticProps", "getStaticPaths", "getServerSideProps"];
 
+static APP_SERVER_ONLY_EXPORTS: &[&str; 4] = &[
+    "generateStaticParams",
+    "generateMetadata",
+    "generateImageMetadata",
+    "generateSitemaps",
+];
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerOnlyExportsConfig {
+    /// Names of the exports to remove, along with the code only they use.
+    #[serde(default = "default_server_only_exports")]
+    pub exports: Vec<JsWord>,
+}
+
+impl Default for ServerOnlyExportsConfig {
+    fn default() -> Self {
+        ServerOnlyExportsConfig {
+            exports: default_server_only_exports(),
+        }
+    }
+}
+
+fn default_server_only_exports() -> Vec<JsWord> {
+    APP_SERVER_ONLY_EXPORTS
+        .iter()
+        .map(|&export| export.into())
+        .collect()
+}
+
 /// Note: This paths requires running `resolver` **before** running this.
 pub fn next_ssg(eliminated_packages: Rc<RefCell<FxHashSet<String>>>) -> impl Fold {
-    Repeat::new(NextSsg {
+    NextSsg {
         state: State {
             eliminated_packages,
             ..Default::default()
         },
-        in_lhs_of_var: false,
-    })
+    }
+}
+
+/// Removes server-only exports like `generateStaticParams` from client
+/// builds, using the same elimination as [next_ssg].
+///
+/// Note: This paths requires running `resolver` **before** running this.
+pub fn server_only_exports(config: ServerOnlyExportsConfig) -> impl Fold {
+    NextSsg {
+        state: State {
+            server_only_exports: Some(config.exports),
+            ..Default::default()
+        },
+    }
 }
 
 /// State of the transforms. Shared by the analyzer and the transform.
 #[derive(Debug, Default)]
 struct State {
-    /// Identifiers referenced by non-data function codes.
-    ///
-    /// Cleared before running each pass, because we drop ast nodes between the
-    /// passes.
+    /// Identifiers referenced by non-data function codes, excluding the
+    /// declarations of module-level bindings.
     refs_from_other: FxHashSet<Id>,
 
-    /// Identifiers referenced by data functions or derivatives.
-    ///
-    /// Preserved between runs, because we should remember derivatives of data
-    /// functions as the data function itself is already removed.
+    /// Identifiers referenced by data functions.
     refs_from_data_fn: FxHashSet<Id>,
 
-    cur_declaring: FxHashSet<Id>,
+    /// Module-level bindings mapped to the identifiers referenced by their
+    /// declarations.
+    deps: FxHashMap<Id, FxHashSet<Id>>,
+
+    /// Exported declarations of data functions.
+    data_fns: FxHashSet<Id>,
+
+    /// Bindings only reachable from data functions, computed from the graph
+    /// once the analyzer is done.
+    removable: FxHashSet<Id>,
+
+    /// Exports eliminated instead of the `getStaticProps` family, if any.
+    server_only_exports: Option<Vec<JsWord>>,
 
     is_prerenderer: bool,
     is_server_props: bool,
-    done: bool,
 
-    should_run_again: bool,
+    /// First `getStaticProps` or `getStaticPaths` identifier, used to report
+    /// conflicts with `getServerSideProps`.
+    prerenderer_ident: Option<Ident>,
+    /// First `getServerSideProps` identifier.
+    server_props_ident: Option<Ident>,
 
     /// Track the import packages which are eliminated in the
     /// `getServerSideProps`
@@ -57,310 +108,474 @@ struct State {
 
 impl State {
     #[allow(clippy::wrong_self_convention)]
-    fn is_data_identifier(&mut self, i: &Ident) -> Result<bool, Error> {
-        if SSG_EXPORTS.contains(&&*i.sym) {
-            if &*i.sym == "getServerSideProps" {
-                if self.is_prerenderer {
-                    HANDLER.with(|handler| {
-                        handler
-                            .struct_span_err(
-                                i.span,
-                                "You can not use getStaticProps or getStaticPaths with \
-                                 getServerSideProps. To use SSG, please remove getServerSideProps",
-                            )
-                            .emit()
-                    });
-                    bail!("both ssg and ssr functions present");
-                }
-
-                self.is_server_props = true;
-            } else {
-                if self.is_server_props {
-                    HANDLER.with(|handler| {
-                        handler
-                            .struct_span_err(
-                                i.span,
-                                "You can not use getStaticProps or getStaticPaths with \
-                                 getServerSideProps. To use SSG, please remove getServerSideProps",
-                            )
-                            .emit()
-                    });
-                    bail!("both ssg and ssr functions present");
-                }
-
-                self.is_prerenderer = true;
-            }
-
-            Ok(true)
-        } else {
-            Ok(false)
+    fn is_data_identifier(&mut self, i: &Ident) -> bool {
+        if self.server_only_exports.is_some() {
+            return self.is_data_export_name(&i.sym);
         }
-    }
-}
 
-struct Analyzer<'a> {
-    state: &'a mut State,
-    in_lhs_of_var: bool,
-    in_data_fn: bool,
-}
+        if !SSG_EXPORTS.contains(&&*i.sym) {
+            return false;
+        }
 
-impl Analyzer<'_> {
-    fn add_ref(&mut self, id: Id) {
-        tracing::trace!("add_ref({}{:?}, data = {})", id.0, id.1, self.in_data_fn);
-        if self.in_data_fn {
-            self.state.refs_from_data_fn.insert(id);
+        if &*i.sym == "getServerSideProps" {
+            self.is_server_props = true;
+            if self.server_props_ident.is_none() {
+                self.server_props_ident = Some(i.clone());
+            }
         } else {
-            if self.state.cur_declaring.contains(&id) {
-                return;
+            self.is_prerenderer = true;
+            if self.prerenderer_ident.is_none() {
+                self.prerenderer_ident = Some(i.clone());
             }
-
-            self.state.refs_from_other.insert(id);
         }
-    }
-}
 
-impl Fold for Analyzer<'_> {
-    // This is important for reducing binary sizes.
-    noop_fold_type!();
+        true
+    }
 
-    fn fold_binding_ident(&mut self, i: BindingIdent) -> BindingIdent {
-        if !self.in_lhs_of_var || self.in_data_fn {
-            self.add_ref(i.id.to_id());
+    /// Reports pages using both SSG and SSR data functions. Called once after
+    /// the analysis, so the error is emitted only once per module.
+    fn report_conflicting_data_fns(&self) {
+        if let (Some(server_props), Some(prerenderer)) =
+            (&self.server_props_ident, &self.prerenderer_ident)
+        {
+            HANDLER.with(|handler| {
+                handler
+                    .struct_span_err(
+                        server_props.span,
+                        "You can not use getStaticProps or getStaticPaths with \
+                         getServerSideProps. To use SSG, please remove getServerSideProps",
+                    )
+                    .span_label(
+                        server_props.span,
+                        format!("`{}` is defined here", server_props.sym),
+                    )
+                    .span_label(
+                        prerenderer.span,
+                        format!("`{}` is defined here", prerenderer.sym),
+                    )
+                    .emit()
+            });
         }
-
-        i
     }
 
-    fn fold_export_named_specifier(&mut self, s: ExportNamedSpecifier) -> ExportNamedSpecifier {
-        if let ModuleExportName::Ident(id) = &s.orig {
-            if !SSG_EXPORTS.contains(&&*id.sym) {
-                self.add_ref(id.to_id());
-            }
+    fn is_data_export_name(&self, sym: &JsWord) -> bool {
+        match &self.server_only_exports {
+            Some(exports) => exports.contains(sym),
+            None => SSG_EXPORTS.contains(&&**sym),
         }
+    }
 
-        s
+    fn should_remove(&self, id: &Id) -> bool {
+        self.removable.contains(id)
     }
 
-    fn fold_export_decl(&mut self, s: ExportDecl) -> ExportDecl {
-        if let Decl::Var(d) = &s.decl {
-            if d.decls.is_empty() {
-                return s;
-            }
+    /// Returns every identifier reachable from `roots` in the dependency
+    /// graph, including the roots themselves.
+    fn reachable(&self, roots: impl IntoIterator<Item = Id>) -> FxHashSet<Id> {
+        let mut seen = FxHashSet::default();
+        let mut stack = roots.into_iter().collect::<Vec<_>>();
 
-            if let Pat::Ident(id) = &d.decls[0].name {
-                if !SSG_EXPORTS.contains(&&*id.id.sym) {
-                    self.add_ref(id.to_id());
-                }
+        while let Some(id) = stack.pop() {
+            if seen.contains(&id) {
+                continue;
+            }
+            if let Some(deps) = self.deps.get(&id) {
+                stack.extend(deps.iter().filter(|dep| !seen.contains(*dep)).cloned());
             }
+            seen.insert(id);
         }
 
-        s.fold_children_with(self)
+        seen
     }
 
-    fn fold_expr(&mut self, e: Expr) -> Expr {
-        let e = e.fold_children_with(self);
+    /// A binding is removed if a data function depends on it, and no code
+    /// which survives the transform does.
+    ///
+    /// Declarations which are not reachable from data functions are always
+    /// preserved, so their dependencies are roots just like the references
+    /// from non-data code.
+    fn compute_removable(&mut self) {
+        let from_data_fn = self.reachable(self.refs_from_data_fn.iter().cloned());
+
+        let roots = self
+            .refs_from_other
+            .iter()
+            .chain(
+                self.deps
+                    .iter()
+                    .filter(|(id, _)| !from_data_fn.contains(*id))
+                    .flat_map(|(_, deps)| deps.iter()),
+            )
+            .cloned()
+            .collect::<Vec<_>>();
+        let from_other = self.reachable(roots);
+
+        self.removable = from_data_fn
+            .into_iter()
+            .filter(|id| !from_other.contains(id))
+            .collect();
+    }
+}
 
-        if let Expr::Ident(i) = &e {
-            self.add_ref(i.to_id());
-        }
+/// Builds the dependency graph of module-level bindings.
+struct Analyzer<'a> {
+    state: &'a mut State,
+}
 
-        e
+impl Analyzer<'_> {
+    fn collect_refs<N>(&self, n: &N, in_data_fn: bool) -> FxHashSet<Id>
+    where
+        N: for<'aa> VisitWith<RefCollector<'aa>>,
+    {
+        let mut refs = FxHashSet::default();
+        n.visit_with(&mut RefCollector {
+            refs: &mut refs,
+            in_lhs_of_var: false,
+            in_data_fn,
+        });
+        refs
     }
 
-    fn fold_jsx_element(&mut self, jsx: JSXElement) -> JSXElement {
-        fn get_leftmost_id_member_expr(e: &JSXMemberExpr) -> Id {
-            match &e.obj {
-                JSXObject::Ident(i) => i.to_id(),
-                JSXObject::JSXMemberExpr(e) => get_leftmost_id_member_expr(e),
-            }
-        }
+    fn add_binding(&mut self, id: Id, refs: &FxHashSet<Id>) {
+        tracing::trace!("add_binding({}{:?}, deps = {})", id.0, id.1, refs.len());
+        self.state
+            .deps
+            .entry(id)
+            .or_default()
+            .extend(refs.iter().cloned());
+    }
 
-        match &jsx.opening.name {
-            JSXElementName::Ident(i) => {
-                self.add_ref(i.to_id());
+    fn analyze_fn_decl(&mut self, f: &FnDecl, is_exported: bool) {
+        let is_data_identifier = self.state.is_data_identifier(&f.ident);
+        tracing::trace!(
+            "ssg: Handling `{}{:?}`; is_data_identifier = {:?}",
+            f.ident.sym,
+            f.ident.span.ctxt,
+            is_data_identifier
+        );
+
+        let refs = self.collect_refs(&f.function, is_data_identifier);
+
+        if is_data_identifier {
+            self.state.refs_from_data_fn.extend(refs);
+            if is_exported {
+                self.state.data_fns.insert(f.ident.to_id());
+            } else {
+                self.state.refs_from_data_fn.insert(f.ident.to_id());
             }
-            JSXElementName::JSXMemberExpr(e) => {
-                self.add_ref(get_leftmost_id_member_expr(e));
+        } else {
+            self.add_binding(f.ident.to_id(), &refs);
+            if is_exported {
+                self.state.refs_from_other.insert(f.ident.to_id());
             }
-            _ => {}
         }
-
-        jsx.fold_children_with(self)
     }
 
-    fn fold_fn_decl(&mut self, f: FnDecl) -> FnDecl {
-        let old_in_data = self.in_data_fn;
+    fn analyze_var_decl(&mut self, v: &VarDecl, is_exported: bool) {
+        for d in &v.decls {
+            let is_data_identifier = match &d.name {
+                Pat::Ident(name) => self.state.is_data_identifier(&name.id),
+                _ => false,
+            };
+
+            let refs = self.collect_refs(d, is_data_identifier);
 
-        self.state.cur_declaring.insert(f.ident.to_id());
+            if is_data_identifier {
+                self.state.refs_from_data_fn.extend(refs);
+                if let Pat::Ident(name) = &d.name {
+                    if is_exported {
+                        self.state.data_fns.insert(name.id.to_id());
+                    }
+                }
+                continue;
+            }
 
-        if let Ok(is_data_identifier) = self.state.is_data_identifier(&f.ident) {
-            self.in_data_fn |= is_data_identifier;
-        } else {
-            return f;
+            // Every binding of a declarator keeps its initializer alive.
+            for id in find_pat_ids::<_, Id>(&d.name) {
+                self.add_binding(id.clone(), &refs);
+                if is_exported {
+                    self.state.refs_from_other.insert(id);
+                }
+            }
         }
-        tracing::trace!(
-            "ssg: Handling `{}{:?}`; in_data_fn = {:?}",
-            f.ident.sym,
-            f.ident.span.ctxt,
-            self.in_data_fn
-        );
+    }
 
-        let f = f.fold_children_with(self);
+    fn analyze_named_export(&mut self, n: &NamedExport) {
+        for s in &n.specifiers {
+            let (exported, orig) = match s {
+                ExportSpecifier::Named(ExportNamedSpecifier {
+                    orig: ModuleExportName::Ident(orig),
+                    exported: Some(ModuleExportName::Ident(exported)),
+                    ..
+                }) => (exported, Some(orig)),
+                ExportSpecifier::Named(ExportNamedSpecifier {
+                    orig: ModuleExportName::Ident(orig),
+                    exported: None,
+                    ..
+                }) => (orig, Some(orig)),
+                ExportSpecifier::Namespace(ExportNamespaceSpecifier {
+                    name: ModuleExportName::Ident(exported),
+                    ..
+                })
+                | ExportSpecifier::Default(ExportDefaultSpecifier { exported, .. }) => {
+                    (exported, None)
+                }
+                _ => continue,
+            };
 
-        self.state.cur_declaring.remove(&f.ident.to_id());
+            let is_data_identifier = self.state.is_data_identifier(exported);
 
-        self.in_data_fn = old_in_data;
+            // Re-exports don't reference any local binding.
+            if n.src.is_some() {
+                continue;
+            }
 
-        f
+            if let Some(orig) = orig {
+                if is_data_identifier {
+                    self.state.refs_from_data_fn.insert(orig.to_id());
+                } else {
+                    self.state.refs_from_other.insert(orig.to_id());
+                }
+            }
+        }
     }
 
-    fn fold_fn_expr(&mut self, f: FnExpr) -> FnExpr {
-        let f = f.fold_children_with(self);
+    fn analyze_module_items(&mut self, items: &[ModuleItem]) {
+        for item in items {
+            match item {
+                // Imports are leaves of the graph.
+                ModuleItem::ModuleDecl(ModuleDecl::Import(..)) => {}
 
-        if let Some(id) = &f.ident {
-            self.add_ref(id.to_id());
-        }
+                ModuleItem::Stmt(Stmt::Decl(Decl::Fn(f))) => self.analyze_fn_decl(f, false),
 
-        f
-    }
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    decl: Decl::Fn(f),
+                    ..
+                })) => self.analyze_fn_decl(f, true),
 
-    /// Drops [ExportDecl] if all specifiers are removed.
-    fn fold_module_item(&mut self, s: ModuleItem) -> ModuleItem {
-        match s {
-            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(e)) if !e.specifiers.is_empty() => {
-                let e = e.fold_with(self);
+                ModuleItem::Stmt(Stmt::Decl(Decl::Var(v))) => self.analyze_var_decl(v, false),
 
-                if e.specifiers.is_empty() {
-                    return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
-                }
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    decl: Decl::Var(v),
+                    ..
+                })) => self.analyze_var_decl(v, true),
 
-                return ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(e));
-            }
-            _ => {}
-        };
-
-        // Visit children to ensure that all references is added to the scope.
-        let s = s.fold_children_with(self);
-
-        if let ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(e)) = &s {
-            match &e.decl {
-                Decl::Fn(f) => {
-                    // Drop getStaticProps.
-                    if let Ok(is_data_identifier) = self.state.is_data_identifier(&f.ident) {
-                        if is_data_identifier {
-                            return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
-                        }
-                    } else {
-                        return s;
-                    }
-                }
+                ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(n)) => self.analyze_named_export(n),
 
-                Decl::Var(d) => {
-                    if d.decls.is_empty() {
-                        return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
-                    }
+                _ => {
+                    let refs = self.collect_refs(item, false);
+                    self.state.refs_from_other.extend(refs);
                 }
-                _ => {}
             }
         }
+    }
+}
+
+/// Collects identifiers referenced by a node.
+struct RefCollector<'a> {
+    refs: &'a mut FxHashSet<Id>,
+    in_lhs_of_var: bool,
+    in_data_fn: bool,
+}
 
-        s
+impl RefCollector<'_> {
+    fn add_ref(&mut self, id: Id) {
+        self.refs.insert(id);
     }
+}
 
-    fn fold_named_export(&mut self, mut n: NamedExport) -> NamedExport {
-        if n.src.is_some() {
-            n.specifiers = n.specifiers.fold_with(self);
-        }
+impl Visit for RefCollector<'_> {
+    // This is important for reducing binary sizes.
+    noop_visit_type!();
 
-        n
+    fn visit_binding_ident(&mut self, i: &BindingIdent) {
+        if !self.in_lhs_of_var || self.in_data_fn {
+            self.add_ref(i.id.to_id());
+        }
     }
 
-    fn fold_prop(&mut self, p: Prop) -> Prop {
-        let p = p.fold_children_with(self);
+    fn visit_expr(&mut self, e: &Expr) {
+        e.visit_children_with(self);
 
-        if let Prop::Shorthand(i) = &p {
+        if let Expr::Ident(i) = &e {
             self.add_ref(i.to_id());
         }
+    }
 
-        p
+    fn visit_fn_expr(&mut self, f: &FnExpr) {
+        f.visit_children_with(self);
+
+        if let Some(id) = &f.ident {
+            self.add_ref(id.to_id());
+        }
     }
 
-    fn fold_var_declarator(&mut self, mut v: VarDeclarator) -> VarDeclarator {
-        let old_in_data = self.in_data_fn;
+    fn visit_jsx_element_name(&mut self, n: &JSXElementName) {
+        fn get_leftmost_id_member_expr(e: &JSXMemberExpr) -> Id {
+            match &e.obj {
+                JSXObject::Ident(i) => i.to_id(),
+                JSXObject::JSXMemberExpr(e) => get_leftmost_id_member_expr(e),
+            }
+        }
 
-        if let Pat::Ident(name) = &v.name {
-            if let Ok(is_data_identifier) = self.state.is_data_identifier(&name.id) {
-                if is_data_identifier {
-                    self.in_data_fn = true;
-                }
-            } else {
-                return v;
+        match n {
+            JSXElementName::Ident(i) => {
+                self.add_ref(i.to_id());
             }
+            JSXElementName::JSXMemberExpr(e) => {
+                self.add_ref(get_leftmost_id_member_expr(e));
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_prop(&mut self, p: &Prop) {
+        p.visit_children_with(self);
+
+        if let Prop::Shorthand(i) = &p {
+            self.add_ref(i.to_id());
         }
+    }
 
+    fn visit_var_declarator(&mut self, v: &VarDeclarator) {
         let old_in_lhs_of_var = self.in_lhs_of_var;
 
         self.in_lhs_of_var = true;
-        v.name = v.name.fold_with(self);
+        v.name.visit_with(self);
 
         self.in_lhs_of_var = false;
-        v.init = v.init.fold_with(self);
+        v.init.visit_with(self);
 
         self.in_lhs_of_var = old_in_lhs_of_var;
-
-        self.in_data_fn = old_in_data;
-
-        v
     }
 }
 
 /// Actual implementation of the transform.
 struct NextSsg {
     pub state: State,
-    in_lhs_of_var: bool,
 }
 
 impl NextSsg {
-    fn should_remove(&self, id: Id) -> bool {
-        self.state.refs_from_data_fn.contains(&id) && !self.state.refs_from_other.contains(&id)
-    }
+    /// This methods returns [Pat::Invalid] if the pattern should be removed.
+    fn prune_pat(&mut self, p: Pat) -> Pat {
+        match p {
+            Pat::Ident(name) => {
+                if self.state.should_remove(&name.id.to_id()) {
+                    tracing::trace!(
+                        "Dropping var `{}{:?}` because it should be removed",
+                        name.id.sym,
+                        name.id.span.ctxt
+                    );
 
-    /// Mark identifiers in `n` as a candidate for removal.
-    fn mark_as_candidate<N>(&mut self, n: N) -> N
-    where
-        N: for<'aa> FoldWith<Analyzer<'aa>>,
-    {
-        tracing::debug!("mark_as_candidate");
+                    return Pat::Invalid(Invalid { span: DUMMY_SP });
+                }
 
-        // Analyzer never change `in_data_fn` to false, so all identifiers in `n` will
-        // be marked as referenced from a data function.
-        let mut v = Analyzer {
-            state: &mut self.state,
-            in_lhs_of_var: false,
-            in_data_fn: true,
-        };
+                Pat::Ident(name)
+            }
+            Pat::Array(mut arr) => {
+                if !arr.elems.is_empty() {
+                    arr.elems = take(&mut arr.elems)
+                        .into_iter()
+                        .map(|e| e.map(|e| self.prune_pat(e)))
+                        .filter(|e| !matches!(e, Some(Pat::Invalid(..))))
+                        .collect();
+
+                    if arr.elems.is_empty() {
+                        return Pat::Invalid(Invalid { span: DUMMY_SP });
+                    }
+                }
 
-        let n = n.fold_with(&mut v);
-        self.state.should_run_again = true;
-        n
-    }
-}
+                Pat::Array(arr)
+            }
+            Pat::Object(mut obj) => {
+                if !obj.props.is_empty() {
+                    obj.props = take(&mut obj.props)
+                        .into_iter()
+                        .filter_map(|prop| match prop {
+                            ObjectPatProp::KeyValue(mut prop) => {
+                                prop.value = Box::new(self.prune_pat(*prop.value));
+                                if prop.value.is_invalid() {
+                                    None
+                                } else {
+                                    Some(ObjectPatProp::KeyValue(prop))
+                                }
+                            }
+                            ObjectPatProp::Assign(prop) => {
+                                if self.state.should_remove(&prop.key.to_id()) {
+                                    None
+                                } else {
+                                    Some(ObjectPatProp::Assign(prop))
+                                }
+                            }
+                            ObjectPatProp::Rest(mut prop) => {
+                                prop.arg = Box::new(self.prune_pat(*prop.arg));
+                                if prop.arg.is_invalid() {
+                                    None
+                                } else {
+                                    Some(ObjectPatProp::Rest(prop))
+                                }
+                            }
+                        })
+                        .collect();
+
+                    if obj.props.is_empty() {
+                        return Pat::Invalid(Invalid { span: DUMMY_SP });
+                    }
+                }
+
+                Pat::Object(obj)
+            }
+            Pat::Rest(mut rest) => {
+                rest.arg = Box::new(self.prune_pat(*rest.arg));
+                if rest.arg.is_invalid() {
+                    return Pat::Invalid(Invalid { span: DUMMY_SP });
+                }
 
-impl Repeated for NextSsg {
-    fn changed(&self) -> bool {
-        self.state.should_run_again
+                Pat::Rest(rest)
+            }
+            Pat::Assign(mut assign) => {
+                assign.left = Box::new(self.prune_pat(*assign.left));
+                if assign.left.is_invalid() {
+                    return Pat::Invalid(Invalid { span: DUMMY_SP });
+                }
+
+                Pat::Assign(assign)
+            }
+            _ => p,
+        }
     }
 
-    fn reset(&mut self) {
-        self.state.refs_from_other.clear();
-        self.state.cur_declaring.clear();
-        self.state.should_run_again = false;
+    /// Drops declarators of data functions and declarators whose bindings
+    /// are all removed.
+    fn prune_var_decl(&mut self, mut v: Box<VarDecl>) -> Box<VarDecl> {
+        v.decls = take(&mut v.decls)
+            .into_iter()
+            .filter_map(|mut d| {
+                if let Pat::Ident(name) = &d.name {
+                    if self.state.data_fns.contains(&name.id.to_id()) {
+                        return None;
+                    }
+                }
+
+                d.name = self.prune_pat(d.name);
+                if d.name.is_invalid() {
+                    None
+                } else {
+                    Some(d)
+                }
+            })
+            .collect();
+
+        v
     }
 }
 
 /// `VisitMut` is faster than [Fold], but we use [Fold] because it's much easier
 /// to read.
 ///
+/// Only module-level items are folded, as the dependency graph only tracks
+/// module-level bindings.
+///
 /// Note: We don't implement `fold_script` because next.js doesn't use it.
 impl Fold for NextSsg {
     // This is important for reducing binary sizes.
@@ -378,7 +593,7 @@ impl Fold for NextSsg {
             ImportSpecifier::Named(ImportNamedSpecifier { local, .. })
             | ImportSpecifier::Default(ImportDefaultSpecifier { local, .. })
             | ImportSpecifier::Namespace(ImportStarAsSpecifier { local, .. }) => {
-                if self.should_remove(local.to_id()) {
+                if self.state.should_remove(&local.to_id()) {
                     if self.state.is_server_props
                         // filter out non-packages import
                         // third part packages must start with `a-z` or `@`
@@ -395,7 +610,6 @@ impl Fold for NextSsg {
                         local.span.ctxt
                     );
 
-                    self.state.should_run_again = true;
                     false
                 } else {
                     true
@@ -406,48 +620,97 @@ impl Fold for NextSsg {
         i
     }
 
-    fn fold_module(&mut self, mut m: Module) -> Module {
+    fn fold_module(&mut self, m: Module) -> Module {
         tracing::info!("ssg: Start");
         {
             // Fill the state.
             let mut v = Analyzer {
                 state: &mut self.state,
-                in_lhs_of_var: false,
-                in_data_fn: false,
             };
-            m = m.fold_with(&mut v);
+            v.analyze_module_items(&m.body);
         }
-
-        // TODO: Use better detection logic
-        // if !self.state.is_prerenderer && !self.state.is_server_props {
-        //     return m;
-        // }
+        self.state.report_conflicting_data_fns();
+        self.state.compute_removable();
 
         m.fold_children_with(self)
     }
 
     fn fold_module_item(&mut self, i: ModuleItem) -> ModuleItem {
-        if let ModuleItem::ModuleDecl(ModuleDecl::Import(i)) = i {
-            let is_for_side_effect = i.specifiers.is_empty();
-            let i = i.fold_with(self);
+        match i {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(i)) => {
+                let is_for_side_effect = i.specifiers.is_empty();
+                let i = i.fold_with(self);
 
-            if !is_for_side_effect && i.specifiers.is_empty() {
-                return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
+                if !is_for_side_effect && i.specifiers.is_empty() {
+                    return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
+                }
+
+                ModuleItem::ModuleDecl(ModuleDecl::Import(i))
             }
 
-            return ModuleItem::ModuleDecl(ModuleDecl::Import(i));
-        }
+            ModuleItem::Stmt(Stmt::Decl(Decl::Fn(f))) => {
+                if self.state.should_remove(&f.ident.to_id()) {
+                    tracing::trace!(
+                        "Dropping function `{}{:?}` because it should be removed",
+                        f.ident.sym,
+                        f.ident.span.ctxt
+                    );
+                    return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
+                }
+
+                ModuleItem::Stmt(Stmt::Decl(Decl::Fn(f)))
+            }
 
-        let i = i.fold_children_with(self);
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                decl: Decl::Fn(f),
+                span,
+            })) => {
+                // Drop getStaticProps.
+                if self.state.data_fns.contains(&f.ident.to_id()) {
+                    return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
+                }
 
-        match &i {
-            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(e)) if e.specifiers.is_empty() => {
-                return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }))
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    decl: Decl::Fn(f),
+                    span,
+                }))
             }
-            _ => {}
-        }
 
-        i
+            ModuleItem::Stmt(Stmt::Decl(Decl::Var(v))) => {
+                let v = self.prune_var_decl(v);
+                if v.decls.is_empty() {
+                    return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
+                }
+
+                ModuleItem::Stmt(Stmt::Decl(Decl::Var(v)))
+            }
+
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                decl: Decl::Var(v),
+                span,
+            })) => {
+                let v = self.prune_var_decl(v);
+                if v.decls.is_empty() {
+                    return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
+                }
+
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    decl: Decl::Var(v),
+                    span,
+                }))
+            }
+
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(e)) => {
+                let e = e.fold_with(self);
+                if e.specifiers.is_empty() {
+                    return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
+                }
+
+                ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(e))
+            }
+
+            _ => i,
+        }
     }
 
     fn fold_module_items(&mut self, mut items: Vec<ModuleItem>) -> Vec<ModuleItem> {
@@ -456,70 +719,63 @@ impl Fold for NextSsg {
         // Drop nodes.
         items.retain(|s| !matches!(s, ModuleItem::Stmt(Stmt::Empty(..))));
 
-        if !self.state.done
-            && !self.state.should_run_again
-            && (self.state.is_prerenderer || self.state.is_server_props)
+        if (self.state.is_prerenderer || self.state.is_server_props)
+            && items.iter().any(|s| s.is_module_decl())
         {
-            self.state.done = true;
-
-            if items.iter().any(|s| s.is_module_decl()) {
-                let mut var = Some(VarDeclarator {
+            let mut var = Some(VarDeclarator {
+                span: DUMMY_SP,
+                name: Pat::Ident(
+                    Ident::new(
+                        if self.state.is_prerenderer {
+                            "__N_SSG".into()
+                        } else {
+                            "__N_SSP".into()
+                        },
+                        DUMMY_SP,
+                    )
+                    .into(),
+                ),
+                init: Some(Box::new(Expr::Lit(Lit::Bool(Bool {
                     span: DUMMY_SP,
-                    name: Pat::Ident(
-                        Ident::new(
-                            if self.state.is_prerenderer {
-                                "__N_SSG".into()
-                            } else {
-                                "__N_SSP".into()
-                            },
-                            DUMMY_SP,
-                        )
-                        .into(),
-                    ),
-                    init: Some(Box::new(Expr::Lit(Lit::Bool(Bool {
-                        span: DUMMY_SP,
-                        value: true,
-                    })))),
-                    definite: Default::default(),
-                });
-
-                let mut new = Vec::with_capacity(items.len() + 1);
-                for item in take(&mut items) {
-                    if let ModuleItem::ModuleDecl(
-                        ModuleDecl::ExportNamed(..)
-                        | ModuleDecl::ExportDecl(..)
-                        | ModuleDecl::ExportDefaultDecl(..)
-                        | ModuleDecl::ExportDefaultExpr(..),
-                    ) = &item
-                    {
-                        if let Some(var) = var.take() {
-                            new.push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    value: true,
+                })))),
+                definite: Default::default(),
+            });
+
+            let mut new = Vec::with_capacity(items.len() + 1);
+            for item in take(&mut items) {
+                if let ModuleItem::ModuleDecl(
+                    ModuleDecl::ExportNamed(..)
+                    | ModuleDecl::ExportDecl(..)
+                    | ModuleDecl::ExportDefaultDecl(..)
+                    | ModuleDecl::ExportDefaultExpr(..),
+                ) = &item
+                {
+                    if let Some(var) = var.take() {
+                        new.push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                            span: DUMMY_SP,
+                            decl: Decl::Var(Box::new(VarDecl {
                                 span: DUMMY_SP,
-                                decl: Decl::Var(Box::new(VarDecl {
-                                    span: DUMMY_SP,
-                                    kind: VarDeclKind::Var,
-                                    declare: Default::default(),
-                                    decls: vec![var],
-                                })),
-                            })))
-                        }
+                                kind: VarDeclKind::Var,
+                                declare: Default::default(),
+                                decls: vec![var],
+                            })),
+                        })))
                     }
-
-                    new.push(item);
                 }
 
-                return new;
+                new.push(item);
             }
+
+            return new;
         }
 
         items
     }
 
     fn fold_named_export(&mut self, mut n: NamedExport) -> NamedExport {
-        n.specifiers = n.specifiers.fold_with(self);
-
         n.specifiers.retain(|s| {
-            let preserve = match s {
+            let exported = match s {
                 ExportSpecifier::Namespace(ExportNamespaceSpecifier {
                     name: ModuleExportName::Ident(exported),
                     ..
@@ -528,165 +784,24 @@ impl Fold for NextSsg {
                 | ExportSpecifier::Named(ExportNamedSpecifier {
                     exported: Some(ModuleExportName::Ident(exported)),
                     ..
-                }) => self
-                    .state
-                    .is_data_identifier(exported)
-                    .map(|is_data_identifier| !is_data_identifier),
+                }) => exported,
                 ExportSpecifier::Named(ExportNamedSpecifier {
                     orig: ModuleExportName::Ident(orig),
                     ..
-                }) => self
-                    .state
-                    .is_data_identifier(orig)
-                    .map(|is_data_identifier| !is_data_identifier),
+                }) => orig,
 
-                _ => Ok(true),
+                _ => return true,
             };
 
-            match preserve {
-                Ok(false) => {
-                    tracing::trace!("Dropping a export specifier because it's a data identifier");
-
-                    if let ExportSpecifier::Named(ExportNamedSpecifier {
-                        orig: ModuleExportName::Ident(orig),
-                        ..
-                    }) = s
-                    {
-                        self.state.should_run_again = true;
-                        self.state.refs_from_data_fn.insert(orig.to_id());
-                    }
+            if self.state.is_data_export_name(&exported.sym) {
+                tracing::trace!("Dropping a export specifier because it's a data identifier");
 
-                    false
-                }
-                Ok(true) => true,
-                Err(_) => false,
+                return false;
             }
+
+            true
         });
 
         n
     }
-
-    /// This methods returns [Pat::Invalid] if the pattern should be removed.
-    fn fold_pat(&mut self, mut p: Pat) -> Pat {
-        p = p.fold_children_with(self);
-
-        if self.in_lhs_of_var {
-            match &mut p {
-                Pat::Ident(name) => {
-                    if self.should_remove(name.id.to_id()) {
-                        self.state.should_run_again = true;
-                        tracing::trace!(
-                            "Dropping var `{}{:?}` because it should be removed",
-                            name.id.sym,
-                            name.id.span.ctxt
-                        );
-
-                        return Pat::Invalid(Invalid { span: DUMMY_SP });
-                    }
-                }
-                Pat::Array(arr) => {
-                    if !arr.elems.is_empty() {
-                        arr.elems.retain(|e| !matches!(e, Some(Pat::Invalid(..))));
-
-                        if arr.elems.is_empty() {
-                            return Pat::Invalid(Invalid { span: DUMMY_SP });
-                        }
-                    }
-                }
-                Pat::Object(obj) => {
-                    if !obj.props.is_empty() {
-                        obj.props = take(&mut obj.props)
-                            .into_iter()
-                            .filter_map(|prop| match prop {
-                                ObjectPatProp::KeyValue(prop) => {
-                                    if prop.value.is_invalid() {
-                                        None
-                                    } else {
-                                        Some(ObjectPatProp::KeyValue(prop))
-                                    }
-                                }
-                                ObjectPatProp::Assign(prop) => {
-                                    if self.should_remove(prop.key.to_id()) {
-                                        self.mark_as_candidate(prop.value);
-
-                                        None
-                                    } else {
-                                        Some(ObjectPatProp::Assign(prop))
-                                    }
-                                }
-                                ObjectPatProp::Rest(prop) => {
-                                    if prop.arg.is_invalid() {
-                                        None
-                                    } else {
-                                        Some(ObjectPatProp::Rest(prop))
-                                    }
-                                }
-                            })
-                            .collect();
-
-                        if obj.props.is_empty() {
-                            return Pat::Invalid(Invalid { span: DUMMY_SP });
-                        }
-                    }
-                }
-                Pat::Rest(rest) => {
-                    if rest.arg.is_invalid() {
-                        return Pat::Invalid(Invalid { span: DUMMY_SP });
-                    }
-                }
-                _ => {}
-            }
-        }
-
-        p
-    }
-
-    #[allow(clippy::single_match)]
-    fn fold_stmt(&mut self, mut s: Stmt) -> Stmt {
-        match s {
-            Stmt::Decl(Decl::Fn(f)) => {
-                if self.should_remove(f.ident.to_id()) {
-                    self.mark_as_candidate(f.function);
-                    return Stmt::Empty(EmptyStmt { span: DUMMY_SP });
-                }
-
-                s = Stmt::Decl(Decl::Fn(f));
-            }
-            _ => {}
-        }
-
-        let s = s.fold_children_with(self);
-        match s {
-            Stmt::Decl(Decl::Var(v)) if v.decls.is_empty() => {
-                return Stmt::Empty(EmptyStmt { span: DUMMY_SP });
-            }
-            _ => {}
-        }
-
-        s
-    }
-
-    /// This method make `name` of [VarDeclarator] to [Pat::Invalid] if it
-    /// should be removed.
-    fn fold_var_declarator(&mut self, mut d: VarDeclarator) -> VarDeclarator {
-        let old = self.in_lhs_of_var;
-        self.in_lhs_of_var = true;
-        let name = d.name.fold_with(self);
-
-        self.in_lhs_of_var = false;
-        if name.is_invalid() {
-            d.init = self.mark_as_candidate(d.init);
-        }
-        let init = d.init.fold_with(self);
-        self.in_lhs_of_var = old;
-
-        VarDeclarator { name, init, ..d }
-    }
-
-    fn fold_var_declarators(&mut self, mut decls: Vec<VarDeclarator>) -> Vec<VarDeclarator> {
-        decls = decls.fold_children_with(self);
-        decls.retain(|d| !d.name.is_invalid());
-
-        decls
-    }
 }
@@ -1,19 +1,55 @@
+use std::{cell::RefCell, path::Path, rc::Rc};
+
 use chrono::Utc;
+use fxhash::FxHashMap;
+use hex::encode as hex_encode;
+use pathdiff::diff_paths;
+use serde::Serialize;
+use sha1::{Digest, Sha1};
 
 use next_binding::swc::core::{
-    common::{errors::HANDLER, Span, DUMMY_SP},
+    common::{errors::HANDLER, FileName, Span, Spanned, DUMMY_SP},
     ecma::ast::*,
+    ecma::atoms::JsWord,
     ecma::visit::{Fold, FoldWith},
 };
 
-pub fn page_config(is_development: bool, is_page_file: bool) -> impl Fold {
+/// `drop_bundle_marker` makes the marker which replaces the client bundle of
+/// an AMP-only page unique. The build timestamp is used if it is `None`.
+pub fn page_config(
+    is_development: bool,
+    is_page_file: bool,
+    drop_bundle_marker: Option<String>,
+    extracted: Rc<RefCell<Option<PageConfigExport>>>,
+) -> impl Fold {
     PageConfig {
         is_development,
         is_page_file,
+        drop_bundle_marker,
+        extracted,
         ..Default::default()
     }
 }
 
+/// A drop bundle marker which only changes with the page's path relative to
+/// `pages_dir` and its source, so it is the same across checkouts of the
+/// project.
+pub fn content_hash_marker(file_name: &FileName, pages_dir: Option<&Path>, src: &str) -> String {
+    let path = match (file_name, pages_dir) {
+        (FileName::Real(path), Some(pages_dir)) => match diff_paths(path, pages_dir) {
+            Some(rel_path) => rel_path.display().to_string(),
+            None => path.display().to_string(),
+        },
+        _ => file_name.to_string(),
+    };
+
+    let mut hasher = Sha1::new();
+    hasher.update(path.as_bytes());
+    hasher.update(b":");
+    hasher.update(src.as_bytes());
+    hex_encode(hasher.finalize())
+}
+
 pub fn page_config_test() -> impl Fold {
     PageConfig {
         in_test: true,
@@ -22,12 +58,87 @@ pub fn page_config_test() -> impl Fold {
     }
 }
 
+/// The statically known values of a page's `export const config`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageConfigExport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amp: Option<BoolOrString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regions: Option<Regions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_duration: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api: Option<ApiConfig>,
+    #[serde(
+        rename = "unstable_includeFiles",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub unstable_include_files: Option<Vec<String>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body_parser: Option<BodyParser>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_resolver: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_limit: Option<ResponseLimit>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum BoolOrString {
+    Bool(bool),
+    String(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Regions {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum BodyParser {
+    Enabled(bool),
+    Options {
+        #[serde(rename = "sizeLimit", skip_serializing_if = "Option::is_none")]
+        size_limit: Option<SizeLimit>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ResponseLimit {
+    Enabled(bool),
+    Size(SizeLimit),
+}
+
+/// Either a number of bytes or a size such as `'1mb'`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum SizeLimit {
+    Bytes(f64),
+    Size(String),
+}
+
 #[derive(Debug, Default)]
 struct PageConfig {
     drop_bundle: bool,
     in_test: bool,
     is_development: bool,
     is_page_file: bool,
+    drop_bundle_marker: Option<String>,
+    extracted: Rc<RefCell<Option<PageConfigExport>>>,
+    /// Initializers of module-level variables, to resolve `export { config }`.
+    local_inits: FxHashMap<JsWord, Box<Expr>>,
 }
 
 const STRING_LITERAL_DROP_BUNDLE: &str = "__NEXT_DROP_CLIENT_FILE__";
@@ -35,13 +146,16 @@ const CONFIG_KEY: &str = "config";
 
 impl Fold for PageConfig {
     fn fold_module_items(&mut self, items: Vec<ModuleItem>) -> Vec<ModuleItem> {
+        self.local_inits = collect_local_inits(&items);
+
         let mut new_items = vec![];
         for item in items {
             new_items.push(item.fold_with(self));
             if !self.is_development && self.drop_bundle {
-                let timestamp = match self.in_test {
-                    true => String::from("mock_timestamp"),
-                    false => Utc::now().timestamp().to_string(),
+                let marker = match (&self.drop_bundle_marker, self.in_test) {
+                    (Some(marker), _) => marker.clone(),
+                    (None, true) => String::from("mock_timestamp"),
+                    (None, false) => Utc::now().timestamp().to_string(),
                 };
                 return vec![ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
                     decls: vec![VarDeclarator {
@@ -54,7 +168,7 @@ impl Fold for PageConfig {
                             type_ann: None,
                         }),
                         init: Some(Box::new(Expr::Lit(Lit::Str(Str {
-                            value: format!("{} {}", STRING_LITERAL_DROP_BUNDLE, timestamp).into(),
+                            value: format!("{} {}", STRING_LITERAL_DROP_BUNDLE, marker).into(),
                             span: DUMMY_SP,
                             raw: None,
                         })))),
@@ -82,92 +196,53 @@ impl Fold for PageConfig {
                 }
 
                 if is_config {
-                    if let Some(expr) = &decl.init {
-                        if let Expr::Object(obj) = &**expr {
-                            for prop in &obj.props {
-                                if let PropOrSpread::Prop(prop) = prop {
-                                    if let Prop::KeyValue(kv) = &**prop {
-                                        match &kv.key {
-                                            PropName::Ident(ident) => {
-                                                if &ident.sym == "amp" {
-                                                    if let Expr::Lit(Lit::Bool(Bool {
-                                                        value,
-                                                        ..
-                                                    })) = &*kv.value
-                                                    {
-                                                        if *value && self.is_page_file {
-                                                            self.drop_bundle = true;
-                                                        }
-                                                    } else if let Expr::Lit(Lit::Str(_)) =
-                                                        &*kv.value
-                                                    {
-                                                        // Do not replace
-                                                        // bundle
-                                                    } else {
-                                                        self.handle_error(
-                                                            "Invalid value found.",
-                                                            export.span,
-                                                        );
-                                                    }
-                                                }
-                                            }
-                                            _ => {
-                                                self.handle_error(
-                                                    "Invalid property found.",
-                                                    export.span,
-                                                );
-                                            }
-                                        }
-                                    } else {
-                                        self.handle_error(
-                                            "Invalid property or value.",
-                                            export.span,
-                                        );
-                                    }
-                                } else {
-                                    self.handle_error(
-                                        "Property spread is not allowed.",
-                                        export.span,
-                                    );
-                                }
-                            }
-                        } else {
-                            self.handle_error("Expected config to be an object.", export.span);
-                        }
-                    } else {
-                        self.handle_error("Expected config to be an object.", export.span);
-                    }
+                    self.extract_config(decl.init.as_deref(), export.span);
                 }
             }
         }
         export
     }
 
-    fn fold_export_named_specifier(
-        &mut self,
-        specifier: ExportNamedSpecifier,
-    ) -> ExportNamedSpecifier {
-        match &specifier.exported {
-            Some(ident) => {
-                if let ModuleExportName::Ident(ident) = ident {
-                    if &ident.sym == CONFIG_KEY {
-                        self.handle_error("Config cannot be re-exported.", specifier.span)
-                    }
+    fn fold_named_export(&mut self, export: NamedExport) -> NamedExport {
+        for specifier in &export.specifiers {
+            if let ExportSpecifier::Named(specifier) = specifier {
+                let orig = match &specifier.orig {
+                    ModuleExportName::Ident(ident) => &ident.sym,
+                    ModuleExportName::Str(..) => continue,
+                };
+                let exported = match &specifier.exported {
+                    Some(ModuleExportName::Ident(ident)) => &ident.sym,
+                    Some(ModuleExportName::Str(..)) => continue,
+                    None => orig,
+                };
+                if exported != CONFIG_KEY {
+                    continue;
                 }
-            }
-            None => {
-                if let ModuleExportName::Ident(ident) = &specifier.orig {
-                    if &ident.sym == CONFIG_KEY {
-                        self.handle_error("Config cannot be re-exported.", specifier.span)
+
+                // `const config = { ... }; export { config }`
+                match self.local_inits.get(orig) {
+                    Some(init) if export.src.is_none() => {
+                        let init = init.clone();
+                        self.extract_config(Some(&*init), specifier.span)
                     }
+                    _ => self.handle_error("Config cannot be re-exported.", specifier.span),
                 }
             }
         }
-        specifier
+        export
     }
 }
 
 impl PageConfig {
+    fn extract_config(&mut self, init: Option<&Expr>, span: Span) {
+        if let Some(Expr::Object(obj)) = init.map(unwrap_expr) {
+            let config = self.parse_config(obj);
+            *self.extracted.borrow_mut() = Some(config);
+        } else {
+            self.handle_error("Expected config to be an object.", span);
+        }
+    }
+
     fn handle_error(&mut self, details: &str, span: Span) {
         if self.is_page_file {
             let message = format!("Invalid page config export found. {} \
@@ -175,4 +250,217 @@ impl PageConfig {
             HANDLER.with(|handler| handler.struct_span_err(span, &message).emit());
         }
     }
+
+    /// Reports an error for a value of `key` which is not `expected`.
+    fn invalid_value(&mut self, key: &str, expected: &str, span: Span) {
+        self.handle_error(
+            &format!("Invalid value found for `{}`, expected {}.", key, expected),
+            span,
+        );
+    }
+
+    /// Returns the key-value pairs of `obj`, reporting every other kind of
+    /// property.
+    fn key_values<'a>(&mut self, obj: &'a ObjectLit) -> Vec<(&'a str, &'a Expr)> {
+        let mut key_values = vec![];
+        for prop in &obj.props {
+            match prop {
+                PropOrSpread::Prop(prop) => match &**prop {
+                    Prop::KeyValue(kv) => match &kv.key {
+                        PropName::Ident(Ident { sym, .. })
+                        | PropName::Str(Str { value: sym, .. }) => {
+                            key_values.push((&**sym, unwrap_expr(&kv.value)))
+                        }
+                        key => self.handle_error("Invalid property found.", key.span()),
+                    },
+                    prop => self.handle_error("Invalid property or value.", prop.span()),
+                },
+                PropOrSpread::Spread(spread) => self.handle_error(
+                    "Property spread is not allowed.",
+                    spread.dot3_token.to(spread.expr.span()),
+                ),
+            }
+        }
+        key_values
+    }
+
+    fn parse_config(&mut self, obj: &ObjectLit) -> PageConfigExport {
+        let mut config = PageConfigExport::default();
+        for (key, value) in self.key_values(obj) {
+            match key {
+                "amp" => match value {
+                    Expr::Lit(Lit::Bool(Bool { value, .. })) => {
+                        if *value && self.is_page_file {
+                            self.drop_bundle = true;
+                        }
+                        config.amp = Some(BoolOrString::Bool(*value));
+                    }
+                    // Do not replace bundle
+                    Expr::Lit(Lit::Str(Str { value, .. })) => {
+                        config.amp = Some(BoolOrString::String(value.to_string()));
+                    }
+                    _ => self.handle_error("Invalid value found.", value.span()),
+                },
+                "runtime" => config.runtime = self.parse_str(key, value),
+                "regions" => {
+                    config.regions = match value {
+                        Expr::Array(_) => self.parse_strs(key, value).map(Regions::Many),
+                        _ => self.parse_str(key, value).map(Regions::One),
+                    }
+                }
+                "maxDuration" => config.max_duration = self.parse_number(key, value),
+                "api" => config.api = self.parse_api(value),
+                "unstable_includeFiles" => {
+                    config.unstable_include_files = self.parse_strs(key, value)
+                }
+                _ => {}
+            }
+        }
+        config
+    }
+
+    fn parse_api(&mut self, value: &Expr) -> Option<ApiConfig> {
+        let obj = match value {
+            Expr::Object(obj) => obj,
+            _ => {
+                self.invalid_value("api", "an object", value.span());
+                return None;
+            }
+        };
+
+        let mut api = ApiConfig::default();
+        for (key, value) in self.key_values(obj) {
+            match key {
+                "bodyParser" => {
+                    api.body_parser = match value {
+                        Expr::Lit(Lit::Bool(Bool { value, .. })) => {
+                            Some(BodyParser::Enabled(*value))
+                        }
+                        Expr::Object(obj) => {
+                            let mut size_limit = None;
+                            for (key, value) in self.key_values(obj) {
+                                if key == "sizeLimit" {
+                                    size_limit = self.parse_size_limit(key, value);
+                                }
+                            }
+                            Some(BodyParser::Options { size_limit })
+                        }
+                        _ => {
+                            self.invalid_value(key, "a boolean or an object", value.span());
+                            None
+                        }
+                    }
+                }
+                "externalResolver" => api.external_resolver = self.parse_bool(key, value),
+                "responseLimit" => {
+                    api.response_limit = match value {
+                        Expr::Lit(Lit::Bool(Bool { value, .. })) => {
+                            Some(ResponseLimit::Enabled(*value))
+                        }
+                        _ => self.parse_size_limit(key, value).map(ResponseLimit::Size),
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(api)
+    }
+
+    fn parse_bool(&mut self, key: &str, value: &Expr) -> Option<bool> {
+        match value {
+            Expr::Lit(Lit::Bool(Bool { value, .. })) => Some(*value),
+            _ => {
+                self.invalid_value(key, "a boolean", value.span());
+                None
+            }
+        }
+    }
+
+    fn parse_number(&mut self, key: &str, value: &Expr) -> Option<f64> {
+        match value {
+            Expr::Lit(Lit::Num(Number { value, .. })) => Some(*value),
+            _ => {
+                self.invalid_value(key, "a number", value.span());
+                None
+            }
+        }
+    }
+
+    fn parse_str(&mut self, key: &str, value: &Expr) -> Option<String> {
+        match value {
+            Expr::Lit(Lit::Str(Str { value, .. })) => Some(value.to_string()),
+            _ => {
+                self.invalid_value(key, "a string", value.span());
+                None
+            }
+        }
+    }
+
+    fn parse_strs(&mut self, key: &str, value: &Expr) -> Option<Vec<String>> {
+        let elems = match value {
+            Expr::Array(ArrayLit { elems, .. }) => elems,
+            _ => {
+                self.invalid_value(key, "an array of strings", value.span());
+                return None;
+            }
+        };
+
+        let mut strs = vec![];
+        for elem in elems {
+            match elem {
+                Some(ExprOrSpread { spread: None, expr }) => match &**expr {
+                    Expr::Lit(Lit::Str(Str { value, .. })) => strs.push(value.to_string()),
+                    _ => {
+                        self.invalid_value(key, "an array of strings", expr.span());
+                        return None;
+                    }
+                },
+                _ => {
+                    self.invalid_value(key, "an array of strings", value.span());
+                    return None;
+                }
+            }
+        }
+        Some(strs)
+    }
+
+    fn parse_size_limit(&mut self, key: &str, value: &Expr) -> Option<SizeLimit> {
+        match value {
+            Expr::Lit(Lit::Num(Number { value, .. })) => Some(SizeLimit::Bytes(*value)),
+            Expr::Lit(Lit::Str(Str { value, .. })) => Some(SizeLimit::Size(value.to_string())),
+            _ => {
+                self.invalid_value(key, "a number or a string", value.span());
+                None
+            }
+        }
+    }
+}
+
+/// Initializers of module-level variables, to resolve `export { config }`.
+pub(crate) fn collect_local_inits(items: &[ModuleItem]) -> FxHashMap<JsWord, Box<Expr>> {
+    let mut local_inits = FxHashMap::default();
+    for item in items {
+        if let ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl))) = item {
+            for decl in &var_decl.decls {
+                if let (Pat::Ident(ident), Some(init)) = (&decl.name, &decl.init) {
+                    local_inits.insert(ident.id.sym.clone(), init.clone());
+                }
+            }
+        }
+    }
+    local_inits
+}
+
+/// Strips TypeScript wrappers and parentheses, e.g. `{ ... } as const` or
+/// `{ ... } satisfies PageConfig`.
+pub(crate) fn unwrap_expr(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Paren(ParenExpr { expr, .. })
+        | Expr::TsAs(TsAsExpr { expr, .. })
+        | Expr::TsSatisfies(TsSatisfiesExpr { expr, .. })
+        | Expr::TsNonNull(TsNonNullExpr { expr, .. })
+        | Expr::TsConstAssertion(TsConstAssertion { expr, .. })
+        | Expr::TsTypeAssertion(TsTypeAssertion { expr, .. }) => unwrap_expr(expr),
+        _ => expr,
+    }
 }
@@ -1,13 +1,15 @@
-use once_cell::sync::Lazy;
+use fxhash::{FxHashMap, FxHashSet};
+use md5::{Digest, Md5};
+use once_cell::{sync::Lazy, unsync::OnceCell};
 use regex::Regex;
 use serde::Deserialize;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 use next_binding::swc::core::{
     common::{errors::HANDLER, FileName},
     ecma::ast::*,
     ecma::atoms::JsWord,
-    ecma::utils::{quote_ident, ExprFactory},
+    ecma::utils::{private_ident, quote_ident, ExprFactory},
     ecma::visit::{Fold, FoldWith},
 };
 
@@ -25,33 +27,251 @@ impl Default for RelayLanguageConfig {
     }
 }
 
+/// How artifacts are referenced, as in the `jsModuleFormat` of the Relay
+/// compiler.
+#[derive(Copy, Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JsModuleFormat {
+    /// By path.
+    #[default]
+    CommonJS,
+    /// By module name, e.g. `require("FooQuery.graphql")`.
+    Haste,
+}
+
 struct Relay<'a> {
     root_dir: PathBuf,
     pages_dir: Option<PathBuf>,
     file_name: FileName,
     config: &'a Config,
+    /// The project of the file, loaded once a `graphql` tag is found.
+    project: OnceCell<RelayProject>,
+    /// Local bindings of the `graphql` tag.
+    graphql_bindings: FxHashSet<Id>,
+    /// Namespace imports of modules exporting the `graphql` tag.
+    graphql_namespaces: FxHashSet<Id>,
+    /// Artifacts imported with `eagerEsModules`, and their bindings.
+    artifact_imports: Vec<(String, Ident)>,
 }
 
 #[derive(Deserialize, Debug, Default, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Config {
+    #[serde(default)]
     pub src: PathBuf,
+    /// The options below take precedence over the ones of the Relay compiler
+    /// config.
     pub artifact_directory: Option<PathBuf>,
     #[serde(default)]
+    pub language: Option<RelayLanguageConfig>,
+    #[serde(default)]
+    pub eager_es_modules: Option<bool>,
+    /// The directory the other paths are relative to. Defaults to the working
+    /// directory.
+    #[serde(default)]
+    pub root_dir: Option<PathBuf>,
+    /// The Relay compiler config to take the project layout from, relative to
+    /// `root_dir`. `relay.config.json` is used if it exists.
+    #[serde(default)]
+    pub config_file: Option<PathBuf>,
+    /// Modules exporting the `graphql` tag, in addition to `react-relay` and
+    /// `relay-runtime`. Their default export is treated as the tag too, like
+    /// `babel-plugin-relay/macro`.
+    #[serde(default)]
+    pub graphql_modules: Vec<JsWord>,
+    /// Replace queries, mutations and subscriptions with the ID the Relay
+    /// compiler persisted them under, read from their artifact, instead of the
+    /// artifact itself.
+    #[serde(default)]
+    pub persisted_queries: bool,
+    #[serde(default)]
+    pub check_artifacts: ArtifactCheck,
+}
+
+/// How artifacts are checked when a template is transformed.
+#[derive(Copy, Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ArtifactCheck {
+    #[default]
+    None,
+    /// Report templates whose artifact doesn't exist.
+    Exists,
+    /// Also report artifacts whose `hash` doesn't match the template text.
+    Hash,
+}
+
+const GRAPHQL_MODULES: &[&str] = &["react-relay", "relay-runtime"];
+
+/// Where the artifacts of a file are and how they are imported.
+#[derive(Debug, Default, Clone)]
+pub struct RelayProject {
+    /// `None` if artifacts are colocated in `__generated__` directories.
+    pub artifact_directory: Option<PathBuf>,
     pub language: RelayLanguageConfig,
+    pub eager_es_modules: bool,
+    pub js_module_format: JsModuleFormat,
+}
+
+/// A `relay.config.json`, with only the fields affecting artifact paths.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum RelayConfigFile {
+    MultiProject(MultiProjectConfigFile),
+    SingleProject(ProjectConfigFile),
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct MultiProjectConfigFile {
+    #[serde(default)]
+    root: Option<PathBuf>,
+    /// Source directories, and the projects they belong to.
+    sources: FxHashMap<PathBuf, OneOrMany<String>>,
+    projects: FxHashMap<String, ProjectConfigFile>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ProjectConfigFile {
+    /// `output` in multi-project configs.
+    #[serde(default, alias = "output")]
+    artifact_directory: Option<PathBuf>,
+    #[serde(default)]
+    language: Option<RelayLanguageConfig>,
+    #[serde(default)]
+    eager_es_modules: bool,
+    #[serde(default)]
+    js_module_format: JsModuleFormat,
 }
 
-fn pull_first_operation_name_from_tpl(tpl: &TaggedTpl) -> Option<String> {
+impl ProjectConfigFile {
+    fn resolve(&self, root: &Path) -> RelayProject {
+        RelayProject {
+            artifact_directory: self
+                .artifact_directory
+                .as_ref()
+                .map(|dir| join_path(root, dir)),
+            // The default of the Relay compiler.
+            language: self.language.unwrap_or(RelayLanguageConfig::JavaScript),
+            eager_es_modules: self.eager_es_modules,
+            js_module_format: self.js_module_format,
+        }
+    }
+}
+
+impl RelayConfigFile {
+    /// The project `file` belongs to. `config_dir` is the directory of the
+    /// config file. Files of sources shared by several projects are an error,
+    /// as their artifacts could be in any of them.
+    fn project_for(&self, config_dir: &Path, file: &Path) -> Result<Option<RelayProject>, String> {
+        match self {
+            RelayConfigFile::SingleProject(project) => Ok(Some(project.resolve(config_dir))),
+            RelayConfigFile::MultiProject(config) => {
+                let root = match &config.root {
+                    Some(root) => join_path(config_dir, root),
+                    None => config_dir.to_path_buf(),
+                };
+                let projects = config
+                    .sources
+                    .iter()
+                    .map(|(source, projects)| (join_path(&root, source), projects))
+                    .filter(|(source, _)| file.starts_with(source))
+                    .max_by_key(|(source, _)| source.components().count());
+                let name = match projects {
+                    None => return Ok(None),
+                    Some((_, OneOrMany::One(name))) => name,
+                    Some((_, OneOrMany::Many(names))) => match &names[..] {
+                        [] => return Ok(None),
+                        [name] => name,
+                        names => {
+                            return Err(format!(
+                                "`{}` belongs to several projects: {}",
+                                file.display(),
+                                names.join(", ")
+                            ))
+                        }
+                    },
+                };
+                Ok(config
+                    .projects
+                    .get(name)
+                    .map(|project| project.resolve(&root)))
+            }
+        }
+    }
+}
+
+/// Joins `path` to `base`, without the `.` components Relay configs often
+/// start paths with.
+fn join_path(base: &Path, path: &Path) -> PathBuf {
+    base.join(
+        path.components()
+            .filter(|component| !matches!(component, Component::CurDir))
+            .collect::<PathBuf>(),
+    )
+}
+
+/// The kind and name of the first definition in `tpl`, e.g. `("query",
+/// "pagesQuery")`.
+fn pull_first_operation_from_tpl(tpl: &TaggedTpl) -> Option<(String, String)> {
     tpl.tpl.quasis.iter().find_map(|quasis| {
         static OPERATION_REGEX: Lazy<Regex> =
             Lazy::new(|| Regex::new(r"(fragment|mutation|query|subscription) (\w+)").unwrap());
 
         let capture_group = OPERATION_REGEX.captures_iter(&quasis.raw).next();
 
-        capture_group.map(|capture_group| capture_group[2].to_string())
+        capture_group
+            .map(|capture_group| (capture_group[1].to_string(), capture_group[2].to_string()))
     })
 }
 
+/// The ID the Relay compiler persisted the operation of an artifact under, in
+/// `params.id`. The `params` object is parsed, so `id`s of the selections or
+/// of its `metadata` aren't mistaken for it.
+fn artifact_persisted_query_id(artifact: &str) -> Option<String> {
+    static PARAMS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#""params"\s*:\s*"#).unwrap());
+
+    // `params` comes last in the artifact, after the selections.
+    let params = &artifact[PARAMS_REGEX.find_iter(artifact).last()?.end()..];
+    let params = serde_json::Deserializer::from_str(params)
+        .into_iter::<serde_json::Value>()
+        .next()?
+        .ok()?;
+    params.get("id")?.as_str().map(String::from)
+}
+
+/// The MD5 hash of the template an artifact was generated from, which the
+/// Relay compiler assigns to `node.hash`.
+fn artifact_source_hash(artifact: &str) -> Option<&str> {
+    static HASH_REGEX: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"\.hash\s*=\s*["']([0-9a-f]+)["']"#).unwrap());
+
+    HASH_REGEX
+        .captures(artifact)
+        .and_then(|capture_group| capture_group.get(1))
+        .map(|hash| hash.as_str())
+}
+
+/// The MD5 hash of the text of `tpl`, to compare with
+/// [artifact_source_hash].
+fn source_hash(tpl: &TaggedTpl) -> String {
+    let text = tpl
+        .tpl
+        .quasis
+        .iter()
+        .map(|quasis| &*quasis.raw)
+        .collect::<Vec<_>>()
+        .join("");
+    hex::encode(Md5::digest(text.as_bytes()))
+}
+
 fn build_require_expr_from_path(path: &str) -> Expr {
     Expr::Call(CallExpr {
         span: Default::default(),
@@ -67,6 +287,50 @@ fn build_require_expr_from_path(path: &str) -> Expr {
 }
 
 impl<'a> Fold for Relay<'a> {
+    fn fold_module(&mut self, module: Module) -> Module {
+        for item in &module.body {
+            if let ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) = item {
+                self.collect_graphql_bindings(import_decl);
+            }
+        }
+
+        let mut module = module.fold_children_with(self);
+
+        let imports = self.artifact_imports.drain(..).map(|(path, local)| {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+                span: Default::default(),
+                specifiers: vec![ImportSpecifier::Default(ImportDefaultSpecifier {
+                    span: Default::default(),
+                    local,
+                })],
+                src: Box::new(Str {
+                    span: Default::default(),
+                    value: JsWord::from(path),
+                    raw: None,
+                }),
+                type_only: false,
+                asserts: None,
+            }))
+        });
+        // After the directive prologue, so `'use client'` stays a directive.
+        let directives = module
+            .body
+            .iter()
+            .take_while(|item| {
+                matches!(
+                    item,
+                    ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+                        expr: box Expr::Lit(Lit::Str(..)),
+                        ..
+                    }))
+                )
+            })
+            .count();
+        module.body.splice(directives..directives, imports);
+
+        module
+    }
+
     fn fold_expr(&mut self, expr: Expr) -> Expr {
         let expr = expr.fold_children_with(self);
 
@@ -90,12 +354,81 @@ enum BuildRequirePathError {
 }
 
 impl<'a> Relay<'a> {
+    fn project(&self) -> &RelayProject {
+        self.project.get_or_init(|| self.load_project())
+    }
+
+    /// The project of the Relay compiler config, with the options of the Next
+    /// config applied over it.
+    fn load_project(&self) -> RelayProject {
+        let mut project = self.load_config_file_project().unwrap_or(RelayProject {
+            artifact_directory: None,
+            language: RelayLanguageConfig::default(),
+            eager_es_modules: false,
+            js_module_format: JsModuleFormat::CommonJS,
+        });
+        if let Some(artifact_directory) = &self.config.artifact_directory {
+            project.artifact_directory = Some(self.root_dir.join(artifact_directory));
+        }
+        if let Some(language) = self.config.language {
+            project.language = language;
+        }
+        if let Some(eager_es_modules) = self.config.eager_es_modules {
+            project.eager_es_modules = eager_es_modules;
+        }
+        project
+    }
+
+    fn load_config_file_project(&self) -> Option<RelayProject> {
+        let config_file = match &self.config.config_file {
+            Some(config_file) => self.root_dir.join(config_file),
+            None => {
+                let config_file = self.root_dir.join("relay.config.json");
+                if !config_file.is_file() {
+                    return None;
+                }
+                config_file
+            }
+        };
+        let file = match &self.file_name {
+            FileName::Real(file) => self.root_dir.join(file),
+            _ => return None,
+        };
+
+        let project = std::fs::read_to_string(&config_file)
+            .map_err(|err| err.to_string())
+            .and_then(|config| {
+                serde_json::from_str::<RelayConfigFile>(&config).map_err(|err| err.to_string())
+            })
+            .and_then(|config| {
+                config.project_for(config_file.parent().unwrap_or(&self.root_dir), &file)
+            });
+        match project {
+            Ok(project) => project,
+            Err(err) => {
+                HANDLER.with(|handler| {
+                    handler.err(&format!(
+                        "Could not read the Relay config `{}`: {}",
+                        config_file.display(),
+                        err
+                    ))
+                });
+                None
+            }
+        }
+    }
+
     fn path_for_artifact(
         &self,
         real_file_name: &Path,
         definition_name: &str,
     ) -> Result<PathBuf, BuildRequirePathError> {
-        let filename = match &self.config.language {
+        let project = self.project();
+        if project.js_module_format == JsModuleFormat::Haste {
+            return Ok(PathBuf::from(format!("{}.graphql", definition_name)));
+        }
+
+        let filename = match &project.language {
             RelayLanguageConfig::Flow => format!("{}.graphql.js", definition_name),
             RelayLanguageConfig::TypeScript => {
                 format!("{}.graphql.ts", definition_name)
@@ -105,8 +438,8 @@ impl<'a> Relay<'a> {
             }
         };
 
-        if let Some(artifact_directory) = &self.config.artifact_directory {
-            Ok(self.root_dir.join(artifact_directory).join(filename))
+        if let Some(artifact_directory) = &project.artifact_directory {
+            Ok(artifact_directory.join(filename))
         } else if self
             .pages_dir
             .as_ref()
@@ -136,56 +469,204 @@ impl<'a> Relay<'a> {
         }
     }
 
-    fn build_call_expr_from_tpl(&mut self, tpl: &TaggedTpl) -> Option<Expr> {
-        if let Expr::Ident(ident) = &*tpl.tag {
-            if &*ident.sym != "graphql" {
-                return None;
-            }
+    fn collect_graphql_bindings(&mut self, import_decl: &ImportDecl) {
+        let is_configured = self.config.graphql_modules.contains(&import_decl.src.value);
+        if import_decl.type_only
+            || !(is_configured || GRAPHQL_MODULES.contains(&&*import_decl.src.value))
+        {
+            return;
         }
 
-        let operation_name = pull_first_operation_name_from_tpl(tpl);
-
-        match operation_name {
-            None => None,
-            Some(operation_name) => match self.build_require_path(operation_name.as_str()) {
-                Ok(final_path) => Some(build_require_expr_from_path(final_path.to_str().unwrap())),
-                Err(err) => {
-                    let base_error = "Could not transform GraphQL template to a Relay import.";
-                    let error_message = match err {
-                        BuildRequirePathError::FileNameNotReal => "Source file was not a real \
-                                                                   file. This is likely a bug and \
-                                                                   should be reported to Next.js"
-                            .to_string(),
-                        BuildRequirePathError::ArtifactDirectoryExpected { file_name } => {
-                            format!(
-                                "The generated file for `{}` will be created in `pages` \
-                                 directory, which will break production builds. Try moving the \
-                                 file outside of `pages` or set the `artifactDirectory` in the \
-                                 Relay config file.",
-                                file_name
-                            )
-                        }
+        for specifier in &import_decl.specifiers {
+            match specifier {
+                ImportSpecifier::Named(ImportNamedSpecifier {
+                    local,
+                    imported,
+                    is_type_only: false,
+                    ..
+                }) => {
+                    let imported = match imported {
+                        Some(ModuleExportName::Ident(ident)) => &ident.sym,
+                        Some(ModuleExportName::Str(str)) => &str.value,
+                        None => &local.sym,
                     };
+                    if &**imported == "graphql" {
+                        self.graphql_bindings.insert(local.to_id());
+                    }
+                }
+                ImportSpecifier::Default(ImportDefaultSpecifier { local, .. }) if is_configured => {
+                    self.graphql_bindings.insert(local.to_id());
+                }
+                ImportSpecifier::Namespace(ImportStarAsSpecifier { local, .. }) => {
+                    self.graphql_namespaces.insert(local.to_id());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Whether `tag` refers to the `graphql` tag of Relay, either through an
+    /// import binding or a namespace import.
+    fn is_graphql_tag(&self, tag: &Expr) -> bool {
+        match tag {
+            Expr::Ident(ident) => self.graphql_bindings.contains(&ident.to_id()),
+            Expr::Member(MemberExpr {
+                obj: box Expr::Ident(obj),
+                prop: MemberProp::Ident(prop),
+                ..
+            }) => &*prop.sym == "graphql" && self.graphql_namespaces.contains(&obj.to_id()),
+            _ => false,
+        }
+    }
+
+    fn build_call_expr_from_tpl(&mut self, tpl: &TaggedTpl) -> Option<Expr> {
+        if !self.is_graphql_tag(&tpl.tag) {
+            return None;
+        }
+
+        let (kind, operation_name) = pull_first_operation_from_tpl(tpl)?;
+
+        match self.build_require_path(operation_name.as_str()) {
+            Ok(final_path) => {
+                self.check_artifact(tpl, &final_path, &operation_name);
+                if self.config.persisted_queries && kind != "fragment" {
+                    let id = self.read_persisted_query_id(tpl, &final_path, &operation_name)?;
+                    return Some(Expr::Lit(Lit::Str(Str {
+                        span: tpl.span,
+                        value: JsWord::from(id),
+                        raw: None,
+                    })));
+                }
+                let final_path = final_path.to_str().unwrap();
+                if self.project().eager_es_modules {
+                    Some(Expr::Ident(
+                        self.import_artifact(final_path, &operation_name),
+                    ))
+                } else {
+                    Some(build_require_expr_from_path(final_path))
+                }
+            }
+            Err(err) => {
+                let base_error = "Could not transform GraphQL template to a Relay import.";
+                let error_message = match err {
+                    BuildRequirePathError::FileNameNotReal => "Source file was not a real file. \
+                                                               This is likely a bug and should be \
+                                                               reported to Next.js"
+                        .to_string(),
+                    BuildRequirePathError::ArtifactDirectoryExpected { file_name } => {
+                        format!(
+                            "The generated file for `{}` will be created in `pages` directory, \
+                             which will break production builds. Try moving the file outside of \
+                             `pages` or set the `artifactDirectory` in the Relay config file.",
+                            file_name
+                        )
+                    }
+                };
+
+                HANDLER.with(|handler| {
+                    handler.span_err(
+                        tpl.span,
+                        format!("{} {}", base_error, error_message).as_str(),
+                    );
+                });
+
+                None
+            }
+        }
+    }
 
-                    HANDLER.with(|handler| {
-                        handler.span_err(
-                            tpl.span,
-                            format!("{} {}", base_error, error_message).as_str(),
-                        );
-                    });
+    /// Reports an artifact that is missing or, with `ArtifactCheck::Hash`, was
+    /// generated from a different template.
+    fn check_artifact(&self, tpl: &TaggedTpl, path: &Path, operation_name: &str) {
+        if self.config.check_artifacts == ArtifactCheck::None
+            || self.project().js_module_format == JsModuleFormat::Haste
+        {
+            return;
+        }
 
-                    None
+        let path = self.root_dir.join(path);
+        let message = match std::fs::read_to_string(&path) {
+            Err(_) => format!(
+                "The Relay artifact for `{}` was not found at `{}`. Re-run the Relay compiler to \
+                 generate it.",
+                operation_name,
+                path.display()
+            ),
+            Ok(artifact) if self.config.check_artifacts == ArtifactCheck::Hash => {
+                match artifact_source_hash(&artifact) {
+                    Some(hash) if hash != source_hash(tpl) => format!(
+                        "The Relay artifact for `{}` at `{}` is out of date. Re-run the Relay \
+                         compiler to update it.",
+                        operation_name,
+                        path.display()
+                    ),
+                    _ => return,
                 }
-            },
+            }
+            Ok(_) => return,
+        };
+
+        HANDLER.with(|handler| handler.span_err(tpl.span, &message));
+    }
+
+    /// Reads the persisted query ID from the artifact at `path`, reporting an
+    /// error if the operation wasn't persisted.
+    fn read_persisted_query_id(
+        &self,
+        tpl: &TaggedTpl,
+        path: &Path,
+        operation_name: &str,
+    ) -> Option<String> {
+        let path = self.root_dir.join(path);
+        let id = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|artifact| artifact_persisted_query_id(&artifact));
+        if id.is_none() {
+            HANDLER.with(|handler| {
+                handler.span_err(
+                    tpl.span,
+                    &format!(
+                        "Could not find the persisted query ID of `{}` in `{}`. Make sure the \
+                         Relay compiler persists queries and re-run it.",
+                        operation_name,
+                        path.display()
+                    ),
+                )
+            });
         }
+        id
+    }
+
+    /// Imports the artifact at `path` once per module, returning its binding.
+    fn import_artifact(&mut self, path: &str, operation_name: &str) -> Ident {
+        if let Some((_, local)) = self
+            .artifact_imports
+            .iter()
+            .find(|(imported, _)| imported == path)
+        {
+            return local.clone();
+        }
+
+        let local = private_ident!(format!("_{}", operation_name));
+        self.artifact_imports
+            .push((path.to_string(), local.clone()));
+        local
     }
 }
 
 pub fn relay(config: &Config, file_name: FileName, pages_dir: Option<PathBuf>) -> impl Fold + '_ {
+    let cwd = std::env::current_dir().unwrap();
     Relay {
-        root_dir: std::env::current_dir().unwrap(),
+        root_dir: match &config.root_dir {
+            Some(root_dir) => cwd.join(root_dir),
+            None => cwd,
+        },
+        project: OnceCell::new(),
         file_name,
         pages_dir,
         config,
+        graphql_bindings: Default::default(),
+        graphql_namespaces: Default::default(),
+        artifact_imports: Default::default(),
     }
 }
@@ -1,3 +1,4 @@
+use fxhash::FxHashMap;
 use serde::Deserialize;
 
 use next_binding::swc::core::{
@@ -26,14 +27,39 @@ impl Config {
 }
 
 #[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Options {
     #[serde(default)]
     pub exclude: Vec<JsWord>,
+    /// Loggers imported from other modules, whose calls are removed too.
+    #[serde(default)]
+    pub targets: Vec<Target>,
+    #[serde(default)]
+    pub remove_debugger: bool,
+}
+
+/// Methods of a logger exported by `module`.
+///
+/// Without `export`, the default and namespace imports of `module` are the
+/// logger, and named imports of `methods` are called directly. With `export`,
+/// the logger is that export, `default` included.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Target {
+    pub module: JsWord,
+    #[serde(default)]
+    pub export: Option<JsWord>,
+    pub methods: Vec<JsWord>,
 }
 
 struct RemoveConsole {
     exclude: Vec<JsWord>,
+    targets: Vec<Target>,
+    remove_debugger: bool,
     bindings: Vec<AHashSet<Id>>,
+    /// Imported loggers, and the methods to remove.
+    loggers: FxHashMap<Id, Vec<JsWord>>,
+    /// Imported logger methods.
+    logger_methods: AHashSet<Id>,
 }
 
 impl RemoveConsole {
@@ -41,11 +67,75 @@ impl RemoveConsole {
         &ident.sym == "console" && !self.bindings.iter().any(|x| x.contains(&ident.to_id()))
     }
 
+    /// Whether `ident` refers to an import rather than a binding of a nested
+    /// scope.
+    fn is_import(&self, ident: &Ident) -> bool {
+        !self
+            .bindings
+            .iter()
+            .skip(1)
+            .any(|x| x.contains(&ident.to_id()))
+    }
+
+    fn collect_loggers(&mut self, import_decl: &ImportDecl) {
+        if import_decl.type_only {
+            return;
+        }
+
+        for target in &self.targets {
+            if target.module != import_decl.src.value {
+                continue;
+            }
+
+            for specifier in &import_decl.specifiers {
+                let logger = match specifier {
+                    ImportSpecifier::Named(named) => {
+                        let imported = match &named.imported {
+                            Some(ModuleExportName::Ident(ident)) => &ident.sym,
+                            Some(ModuleExportName::Str(str)) => &str.value,
+                            None => &named.local.sym,
+                        };
+                        match &target.export {
+                            Some(export) if export == imported => &named.local,
+                            None if target.methods.contains(imported) => {
+                                self.logger_methods.insert(named.local.to_id());
+                                continue;
+                            }
+                            _ => continue,
+                        }
+                    }
+                    ImportSpecifier::Default(ImportDefaultSpecifier { local, .. })
+                        if target
+                            .export
+                            .as_ref()
+                            .map_or(true, |export| &**export == "default") =>
+                    {
+                        local
+                    }
+                    ImportSpecifier::Namespace(ImportStarAsSpecifier { local, .. })
+                        if target.export.is_none() =>
+                    {
+                        local
+                    }
+                    _ => continue,
+                };
+
+                self.loggers
+                    .entry(logger.to_id())
+                    .or_default()
+                    .extend(target.methods.iter().cloned());
+            }
+        }
+    }
+
     fn should_remove_call(&mut self, n: &CallExpr) -> bool {
         let callee = &n.callee;
         let member_expr = match callee {
             Callee::Expr(e) => match &**e {
                 Expr::Member(m) => m,
+                Expr::Ident(i) => {
+                    return self.logger_methods.contains(&i.to_id()) && self.is_import(i);
+                }
                 _ => return false,
             },
             _ => return false,
@@ -57,9 +147,18 @@ impl RemoveConsole {
             return false;
         }
 
-        // Only proceed if the object is the global `console` object.
+        // Only proceed if the object is the global `console` object, or an imported
+        // logger with the method.
         match &*member_expr.obj {
             Expr::Ident(i) if self.is_global_console(i) => {}
+            Expr::Ident(i) => {
+                return match (self.loggers.get(&i.to_id()), &member_expr.prop) {
+                    (Some(methods), MemberProp::Ident(prop)) => {
+                        methods.contains(&prop.sym) && self.is_import(i)
+                    }
+                    _ => false,
+                };
+            }
             _ => return false,
         }
 
@@ -73,12 +172,73 @@ impl RemoveConsole {
 
         true
     }
+
+    fn push_params(&mut self, params: &[Param]) {
+        let mut new_params: AHashSet<Id> = AHashSet::default();
+        for param in params {
+            new_params.extend(collect_top_level_decls(param));
+        }
+        self.bindings.push(new_params);
+    }
+}
+
+/// Whether evaluating `expr` obviously has no side effects.
+fn is_pure(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(..) | Expr::Ident(..) | Expr::Arrow(..) | Expr::Fn(..) => true,
+        Expr::Tpl(tpl) => tpl.exprs.iter().all(|expr| is_pure(expr)),
+        Expr::Paren(paren) => is_pure(&paren.expr),
+        _ => false,
+    }
+}
+
+/// `void 0`, preceded by the arguments of `call` that may have side effects.
+/// Spread arguments are kept in an array, so their iterators are still
+/// consumed.
+fn replace_call(call: CallExpr) -> Expr {
+    let void_0 = Box::new(Expr::Unary(UnaryExpr {
+        span: DUMMY_SP,
+        op: op!("void"),
+        arg: Box::new(Expr::Lit(Lit::Num(Number {
+            span: DUMMY_SP,
+            value: 0.0,
+            raw: None,
+        }))),
+    }));
+
+    let mut exprs: Vec<Box<Expr>> = call
+        .args
+        .into_iter()
+        .filter_map(|arg| match arg.spread {
+            Some(..) => Some(Box::new(Expr::Array(ArrayLit {
+                span: DUMMY_SP,
+                elems: vec![Some(arg)],
+            }))),
+            None if is_pure(&arg.expr) => None,
+            None => Some(arg.expr),
+        })
+        .collect();
+    if exprs.is_empty() {
+        return *void_0;
+    }
+    exprs.push(void_0);
+
+    Expr::Paren(ParenExpr {
+        span: call.span,
+        expr: Box::new(Expr::Seq(SeqExpr {
+            span: call.span,
+            exprs,
+        })),
+    })
 }
 
 impl Fold for RemoveConsole {
     noop_fold_type!();
 
     fn fold_stmt(&mut self, stmt: Stmt) -> Stmt {
+        if self.remove_debugger && matches!(stmt, Stmt::Debugger(..)) {
+            return Stmt::Empty(EmptyStmt { span: DUMMY_SP });
+        }
         if let Stmt::Expr(e) = &stmt {
             if let Expr::Call(c) = &*e.expr {
                 if self.should_remove_call(c) {
@@ -89,13 +249,34 @@ impl Fold for RemoveConsole {
         stmt.fold_children_with(self)
     }
 
-    fn fold_function(&mut self, mut func: Function) -> Function {
-        let mut new_params: AHashSet<Id> = AHashSet::default();
-        for param in &func.params {
-            new_params.extend(collect_top_level_decls(param));
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        match expr.fold_children_with(self) {
+            Expr::Call(call) if self.should_remove_call(&call) => replace_call(call),
+            expr => expr,
         }
+    }
 
-        self.bindings.push(new_params);
+    fn fold_arrow_expr(&mut self, mut arrow: ArrowExpr) -> ArrowExpr {
+        let params: Vec<Param> = arrow
+            .params
+            .iter()
+            .map(|pat| Param {
+                span: DUMMY_SP,
+                decorators: Default::default(),
+                pat: pat.clone(),
+            })
+            .collect();
+
+        self.push_params(&params);
+        self.bindings.push(collect_top_level_decls(&arrow.body));
+        arrow.body = arrow.body.fold_with(self);
+        self.bindings.pop().unwrap();
+        self.bindings.pop().unwrap();
+        arrow
+    }
+
+    fn fold_function(&mut self, mut func: Function) -> Function {
+        self.push_params(&func.params);
         self.bindings.push(collect_top_level_decls(&func));
         func.body = func.body.fold_with(self);
         self.bindings.pop().unwrap();
@@ -104,6 +285,12 @@ impl Fold for RemoveConsole {
     }
 
     fn fold_module(&mut self, module: Module) -> Module {
+        for item in &module.body {
+            if let ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) = item {
+                self.collect_loggers(import_decl);
+            }
+        }
+
         self.bindings.push(collect_top_level_decls(&module));
         let m = module.fold_children_with(self);
         self.bindings.pop().unwrap();
@@ -119,12 +306,16 @@ impl Fold for RemoveConsole {
 }
 
 pub fn remove_console(config: Config) -> impl Fold {
-    let exclude = match config {
-        Config::WithOptions(x) => x.exclude,
-        _ => vec![],
+    let (exclude, targets, remove_debugger) = match config {
+        Config::WithOptions(x) => (x.exclude, x.targets, x.remove_debugger),
+        _ => (vec![], vec![], false),
     };
     RemoveConsole {
         exclude,
+        targets,
+        remove_debugger,
         bindings: Default::default(),
+        loggers: Default::default(),
+        logger_methods: Default::default(),
     }
 }
@@ -0,0 +1,58 @@
+//! Runs a single transform through the compiler, for tests that check more
+//! than the output of `test_fixture`.
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+
+use next_binding::swc::core::{
+    base::{try_with_handler, Compiler},
+    common::{
+        comments::SingleThreadedComments, FileName, FilePathMapping, SourceFile, SourceMap, GLOBALS,
+    },
+    ecma::transforms::base::pass::noop,
+    ecma::visit::Fold,
+};
+
+pub static COMPILER: Lazy<Arc<Compiler>> = Lazy::new(|| {
+    let cm = Arc::new(SourceMap::new(FilePathMapping::empty()));
+
+    Arc::new(Compiler::new(cm))
+});
+
+pub fn new_source_file(file_name: &str, src: &str) -> Arc<SourceFile> {
+    COMPILER
+        .cm
+        .new_source_file(FileName::Real(file_name.into()), src.to_owned())
+}
+
+/// Runs `pass` on `fm`, returning the output code, or `Err` if an error was
+/// reported.
+pub fn run_pass<P: Fold>(fm: Arc<SourceFile>, pass: impl FnOnce() -> P) -> Result<String, ()> {
+    try_with_handler(COMPILER.cm.clone(), Default::default(), |handler| {
+        GLOBALS.set(&Default::default(), || {
+            let comments = SingleThreadedComments::default();
+            COMPILER.process_js_with_custom_pass(
+                fm,
+                None,
+                handler,
+                &Default::default(),
+                comments,
+                |_| pass(),
+                |_| noop(),
+            )
+        })
+    })
+    .map(|output| output.code)
+    .map_err(|_| ())
+}
+
+/// Runs `pass` on `src` as the file `file_name`.
+pub fn transform<P: Fold>(
+    file_name: &str,
+    src: &str,
+    pass: impl FnOnce() -> P,
+) -> Result<String, ()> {
+    run_pass(new_source_file(file_name, src), pass)
+}
@@ -12,10 +12,12 @@ use next_binding::swc::{
     testing::fixture,
 };
 use next_swc::{
+    amp_attributes::amp_attributes,
     disallow_re_export_all_in_page::disallow_re_export_all_in_page,
     next_dynamic::next_dynamic,
     next_font_loaders::{next_font_loaders, Config as FontLoaderConfig},
     next_ssg::next_ssg,
+    page_config::page_config_test,
     react_server_components::server_components,
     server_actions::{self, server_actions},
 };
@@ -28,6 +30,21 @@ fn syntax() -> Syntax {
     })
 }
 
+#[fixture("tests/errors/amp/**/input.js")]
+fn amp_attributes_errors(input: PathBuf) {
+    let output = input.parent().unwrap().join("output.js");
+    test_fixture(
+        syntax(),
+        &|_tr| amp_attributes(),
+        &input,
+        &output,
+        FixtureTestConfig {
+            allow_error: true,
+            ..Default::default()
+        },
+    );
+}
+
 #[fixture("tests/errors/re-export-all-in-page/**/input.js")]
 fn re_export_all_in_page(input: PathBuf) {
     let output = input.parent().unwrap().join("output.js");
@@ -55,6 +72,9 @@ fn next_dynamic_errors(input: PathBuf) {
                 false,
                 FileName::Real(PathBuf::from("/some-project/src/some-file.js")),
                 Some("/some-project/src".into()),
+                None,
+                Default::default(),
+                Default::default(),
             )
         },
         &input,
@@ -81,6 +101,21 @@ fn next_ssg_errors(input: PathBuf) {
     );
 }
 
+#[fixture("tests/errors/page-config/**/input.js")]
+fn page_config_errors(input: PathBuf) {
+    let output = input.parent().unwrap().join("output.js");
+    test_fixture(
+        syntax(),
+        &|_tr| page_config_test(),
+        &input,
+        &output,
+        FixtureTestConfig {
+            allow_error: true,
+            ..Default::default()
+        },
+    );
+}
+
 #[fixture("tests/errors/react-server-components/server-graph/**/input.js")]
 fn react_server_components_server_graph_errors(input: PathBuf) {
     let output = input.parent().unwrap().join("output.js");
@@ -135,10 +170,44 @@ fn next_font_loaders_errors(input: PathBuf) {
     test_fixture(
         syntax(),
         &|_tr| {
-            next_font_loaders(FontLoaderConfig {
-                relative_file_path_from_root: "pages/test.tsx".into(),
-                font_loaders: vec!["@next/font/google".into(), "cool-fonts".into()],
-            })
+            next_font_loaders(
+                FontLoaderConfig {
+                    relative_file_path_from_root: "pages/test.tsx".into(),
+                    font_loaders: vec![
+                        "@next/font/google".into(),
+                        "cool-fonts".into(),
+                        "strict-fonts".into(),
+                    ],
+                    query_encoding: Default::default(),
+                    argument_schemas: serde_json::from_str(
+                        r#"{
+                            "strict-fonts": {
+                                "*": [{
+                                    "type": "object",
+                                    "properties": {
+                                        "weight": {
+                                            "type": ["string", "array"],
+                                            "items": { "type": "string" }
+                                        },
+                                        "subsets": {
+                                            "type": "array",
+                                            "items": { "enum": ["latin", "latin-ext"] }
+                                        },
+                                        "display": {
+                                            "enum": ["auto", "block", "swap", "fallback", "optional"]
+                                        }
+                                    },
+                                    "required": ["subsets"],
+                                    "additionalProperties": false
+                                }]
+                            }
+                        }"#,
+                    )
+                    .unwrap(),
+                },
+                Default::default(),
+                Default::default(),
+            )
         },
         &input,
         &output,
@@ -0,0 +1,11 @@
+export const config = { amp: true }
+
+export default function Page() {
+  return (
+    <div>
+      <button on="tap:menu.toggle" onClick={() => {}}>
+        Menu
+      </button>
+    </div>
+  )
+}
@@ -0,0 +1,18 @@
+export const config = { amp: 'hybrid' }
+
+export default function Page() {
+  return (
+    <div>
+      <script src="/analytics.js" />
+      <button onClick={() => {}}>Click</button>
+      <div xmlns="http://www.w3.org/1999/xhtml" />
+      <a href="/" download tabIndex={0} aria-label="Home" />
+      <span href="/" />
+      <svg>
+        <filter id="blur">
+          <feGaussianBlur stdDeviation="2" />
+        </filter>
+      </svg>
+    </div>
+  )
+}
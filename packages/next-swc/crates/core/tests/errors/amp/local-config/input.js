@@ -0,0 +1,7 @@
+const config = { amp: true }
+
+export default function Page() {
+  return <script src="/analytics.js" />
+}
+
+export { config }
@@ -0,0 +1,6 @@
+import dynamic from 'next/dynamic'
+
+const options = { ssr: false }
+const A = dynamic(() => import('../components/a'), { ssr: 'false' })
+const B = dynamic(() => import('../components/b'), { loding: () => null })
+const C = dynamic(() => import('../components/c'), { ...options })
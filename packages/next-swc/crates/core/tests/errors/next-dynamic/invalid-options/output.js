@@ -0,0 +1,28 @@
+import dynamic from 'next/dynamic';
+const options = {
+    ssr: false
+};
+const A = dynamic(()=>import('../components/a'), {
+    loadableGenerated: {
+        modules: [
+            "some-file.js -> " + "../components/a"
+        ]
+    },
+    ssr: 'false'
+});
+const B = dynamic(()=>import('../components/b'), {
+    loadableGenerated: {
+        modules: [
+            "some-file.js -> " + "../components/b"
+        ]
+    },
+    loding: ()=>null
+});
+const C = dynamic(()=>import('../components/c'), {
+    loadableGenerated: {
+        modules: [
+            "some-file.js -> " + "../components/c"
+        ]
+    },
+    ...options
+});
@@ -0,0 +1,6 @@
+import { Roboto } from 'strict-fonts'
+const a = Roboto({ weight: 400, subsets: ['latin'] })
+const b = Roboto({ wieght: '400', subsets: ['latin'] })
+const c = Roboto({ weight: '400' })
+const d = Roboto({ subsets: ['latin', 'cyrillic'], display: 'swap' })
+const e = Roboto({ subsets: ['latin'] }, {})
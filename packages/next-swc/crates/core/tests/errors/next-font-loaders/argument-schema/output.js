@@ -0,0 +1,5 @@
+import a from 'strict-fonts/target.css?{"path":"pages/test.tsx","import":"Roboto","arguments":[{"weight":400.0,"subsets":["latin"]}],"variableName":"a"}';
+import b from 'strict-fonts/target.css?{"path":"pages/test.tsx","import":"Roboto","arguments":[{"wieght":"400","subsets":["latin"]}],"variableName":"b"}';
+import c from 'strict-fonts/target.css?{"path":"pages/test.tsx","import":"Roboto","arguments":[{"weight":"400"}],"variableName":"c"}';
+import d from 'strict-fonts/target.css?{"path":"pages/test.tsx","import":"Roboto","arguments":[{"subsets":["latin","cyrillic"],"display":"swap"}],"variableName":"d"}';
+import e from 'strict-fonts/target.css?{"path":"pages/test.tsx","import":"Roboto","arguments":[{"subsets":["latin"]},{}],"variableName":"e"}';
@@ -0,0 +1,7 @@
+import { Inter } from '@next/font/google'
+const a = Inter({ weight: NaN })
+const b = Inter({ weight: -Infinity })
+const c = Inter({ weight: 1e999 })
+const d = Inter({ weight: 10n })
+const e = Inter({ subsets: /latin/ })
+const f = Inter({ 'font-weight': 400, 1.5: 'x' })
@@ -0,0 +1,6 @@
+import a from '@next/font/google/target.css?{"path":"pages/test.tsx","import":"Inter","arguments":[{}],"variableName":"a"}';
+import b from '@next/font/google/target.css?{"path":"pages/test.tsx","import":"Inter","arguments":[{}],"variableName":"b"}';
+import c from '@next/font/google/target.css?{"path":"pages/test.tsx","import":"Inter","arguments":[{}],"variableName":"c"}';
+import d from '@next/font/google/target.css?{"path":"pages/test.tsx","import":"Inter","arguments":[{}],"variableName":"d"}';
+import e from '@next/font/google/target.css?{"path":"pages/test.tsx","import":"Inter","arguments":[{}],"variableName":"e"}';
+import f from '@next/font/google/target.css?{"path":"pages/test.tsx","import":"Inter","arguments":[{"font-weight":400.0,"1.5":"x"}],"variableName":"f"}';
@@ -1,7 +1,7 @@
 import { ABeeZee } from '@next/font/google'
 
-const a = fn({ 10: 'hello' })
-const a = ABeeZee({ 10: 'hello' })
+const a = fn({ [10]: 'hello' })
+const a = ABeeZee({ [10]: 'hello' })
 
 const a = fn({ variant: [i1] })
 const a = ABeeZee({ variant: [i1] })
@@ -3,7 +3,7 @@ import a from '@next/font/google/target.css?{"path":"pages/test.tsx","import":"A
 import a from '@next/font/google/target.css?{"path":"pages/test.tsx","import":"ABeeZee","arguments":[{}],"variableName":"a"}';
 import a from '@next/font/google/target.css?{"path":"pages/test.tsx","import":"ABeeZee","arguments":[{}],"variableName":"a"}';
 const a = fn({
-    10: 'hello'
+    [10]: 'hello'
 });
 const a = fn({
     variant: [
@@ -1,3 +0,0 @@
-export var __N_SSG = true;
-export const getServerSideProps = function getServerSideProps() {
-}
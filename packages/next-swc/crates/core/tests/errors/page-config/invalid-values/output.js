@@ -0,0 +1,4 @@
+export const config = {
+  runtime: 1,
+  api: { bodyParser: 'yes' },
+}
@@ -10,9 +10,9 @@ use next_binding::swc::{
 };
 use next_swc::{
     amp_attributes::amp_attributes,
-    next_dynamic::next_dynamic,
+    next_dynamic::{next_dynamic, Config as NextDynamicConfig, DynamicWrapper, OutputMode},
     next_font_loaders::{next_font_loaders, Config as FontLoaderConfig},
-    next_ssg::next_ssg,
+    next_ssg::{next_ssg, server_only_exports},
     page_config::page_config_test,
     react_remove_properties::remove_properties,
     react_server_components::server_components,
@@ -42,6 +42,16 @@ fn amp_attributes_fixture(input: PathBuf) {
     );
 }
 
+fn next_dynamic_config() -> NextDynamicConfig {
+    NextDynamicConfig {
+        wrappers: vec![DynamicWrapper {
+            module: "@acme/ui".into(),
+            exports: vec!["dynamic".into()],
+        }],
+        ..Default::default()
+    }
+}
+
 #[fixture("tests/fixture/next-dynamic/**/input.js")]
 fn next_dynamic_fixture(input: PathBuf) {
     let output_dev = input.parent().unwrap().join("output-dev.js");
@@ -56,6 +66,9 @@ fn next_dynamic_fixture(input: PathBuf) {
                 false,
                 FileName::Real(PathBuf::from("/some-project/src/some-file.js")),
                 Some("/some-project/src".into()),
+                None,
+                next_dynamic_config(),
+                Default::default(),
             )
         },
         &input,
@@ -71,6 +84,9 @@ fn next_dynamic_fixture(input: PathBuf) {
                 false,
                 FileName::Real(PathBuf::from("/some-project/src/some-file.js")),
                 Some("/some-project/src".into()),
+                None,
+                next_dynamic_config(),
+                Default::default(),
             )
         },
         &input,
@@ -86,6 +102,9 @@ fn next_dynamic_fixture(input: PathBuf) {
                 false,
                 FileName::Real(PathBuf::from("/some-project/src/some-file.js")),
                 Some("/some-project/src".into()),
+                None,
+                next_dynamic_config(),
+                Default::default(),
             )
         },
         &input,
@@ -94,6 +113,76 @@ fn next_dynamic_fixture(input: PathBuf) {
     );
 }
 
+#[fixture("tests/fixture/next-dynamic-app-dir/**/input.js")]
+fn next_dynamic_app_dir_fixture(input: PathBuf) {
+    let output_dev = input.parent().unwrap().join("output-dev.js");
+    let output_ssr = input.parent().unwrap().join("output-ssr.js");
+    test_fixture(
+        syntax(),
+        &|_tr| {
+            next_dynamic(
+                true,
+                false,
+                false,
+                FileName::Real(PathBuf::from("/some-project/src/app/some-file.js")),
+                Some("/some-project/src/pages".into()),
+                Some("/some-project/src/app".into()),
+                Default::default(),
+                Default::default(),
+            )
+        },
+        &input,
+        &output_dev,
+        Default::default(),
+    );
+    test_fixture(
+        syntax(),
+        &|_tr| {
+            next_dynamic(
+                false,
+                true,
+                false,
+                FileName::Real(PathBuf::from("/some-project/src/app/some-file.js")),
+                Some("/some-project/src/pages".into()),
+                Some("/some-project/src/app".into()),
+                Default::default(),
+                Default::default(),
+            )
+        },
+        &input,
+        &output_ssr,
+        Default::default(),
+    );
+}
+
+#[fixture("tests/fixture/next-dynamic-neutral/**/input.js")]
+fn next_dynamic_neutral_fixture(input: PathBuf) {
+    let output_prod = input.parent().unwrap().join("output-prod.js");
+    test_fixture(
+        syntax(),
+        &|_tr| {
+            next_dynamic(
+                false,
+                false,
+                false,
+                FileName::Real(PathBuf::from("/some-project/src/some-file.js")),
+                Some("/some-project/src".into()),
+                None,
+                NextDynamicConfig {
+                    output: OutputMode::Neutral {
+                        helper: "__turbopack_module_id__".into(),
+                    },
+                    ..Default::default()
+                },
+                Default::default(),
+            )
+        },
+        &input,
+        &output_prod,
+        Default::default(),
+    );
+}
+
 #[fixture("tests/fixture/ssg/**/input.js")]
 fn next_ssg_fixture(input: PathBuf) {
     let output = input.parent().unwrap().join("output.js");
@@ -126,6 +215,18 @@ fn next_ssg_fixture(input: PathBuf) {
     );
 }
 
+#[fixture("tests/fixture/server-only-exports/**/input.js")]
+fn server_only_exports_fixture(input: PathBuf) {
+    let output = input.parent().unwrap().join("output.js");
+    test_fixture(
+        syntax(),
+        &|_tr| server_only_exports(Default::default()),
+        &input,
+        &output,
+        Default::default(),
+    );
+}
+
 #[fixture("tests/fixture/page-config/**/input.js")]
 fn page_config_fixture(input: PathBuf) {
     let output = input.parent().unwrap().join("output.js");
@@ -138,21 +239,37 @@ fn page_config_fixture(input: PathBuf) {
     );
 }
 
+#[fixture("tests/fixture/page-config/**/input.ts")]
+fn page_config_typescript_fixture(input: PathBuf) {
+    let output = input.parent().unwrap().join("output.ts");
+    test_fixture(
+        Syntax::Typescript(Default::default()),
+        &|_tr| page_config_test(),
+        &input,
+        &output,
+        Default::default(),
+    );
+}
+
 #[fixture("tests/fixture/relay/**/input.ts*")]
 fn relay_no_artifact_dir_fixture(input: PathBuf) {
     let output = input.parent().unwrap().join("output.js");
     let config = RelayConfig {
-        language: RelayLanguageConfig::TypeScript,
+        language: Some(RelayLanguageConfig::TypeScript),
         artifact_directory: Some(PathBuf::from("__generated__")),
+        graphql_modules: vec!["@acme/relay".into()],
         ..Default::default()
     };
     test_fixture(
         syntax(),
         &|_tr| {
-            relay(
-                &config,
-                FileName::Real(PathBuf::from("input.tsx")),
-                Some(PathBuf::from("src/pages")),
+            chain!(
+                resolver(Mark::new(), Mark::new(), false),
+                relay(
+                    &config,
+                    FileName::Real(PathBuf::from("input.tsx")),
+                    Some(PathBuf::from("src/pages")),
+                )
             )
         },
         &input,
@@ -161,7 +278,7 @@ fn relay_no_artifact_dir_fixture(input: PathBuf) {
     );
 }
 
-#[fixture("tests/fixture/remove-console/**/input.js")]
+#[fixture("tests/fixture/remove-console/all/**/input.js")]
 fn remove_console_fixture(input: PathBuf) {
     let output = input.parent().unwrap().join("output.js");
     test_fixture(
@@ -173,6 +290,37 @@ fn remove_console_fixture(input: PathBuf) {
     );
 }
 
+#[fixture("tests/fixture/remove-console/options/**/input.js")]
+fn remove_console_options_fixture(input: PathBuf) {
+    let output = input.parent().unwrap().join("output.js");
+    test_fixture(
+        syntax(),
+        &|_tr| {
+            remove_console(next_swc::remove_console::Config::WithOptions(
+                next_swc::remove_console::Options {
+                    exclude: vec!["error".into()],
+                    targets: vec![
+                        next_swc::remove_console::Target {
+                            module: "@acme/log".into(),
+                            export: None,
+                            methods: vec!["debug".into(), "trace".into()],
+                        },
+                        next_swc::remove_console::Target {
+                            module: "@acme/logger".into(),
+                            export: Some("logger".into()),
+                            methods: vec!["debug".into()],
+                        },
+                    ],
+                    remove_debugger: true,
+                },
+            ))
+        },
+        &input,
+        &output,
+        Default::default(),
+    );
+}
+
 #[fixture("tests/fixture/react-remove-properties/default/**/input.js")]
 fn react_remove_properties_default_fixture(input: PathBuf) {
     let output = input.parent().unwrap().join("output.js");
@@ -290,10 +438,16 @@ fn next_font_loaders_fixture(input: PathBuf) {
     test_fixture(
         syntax(),
         &|_tr| {
-            next_font_loaders(FontLoaderConfig {
-                relative_file_path_from_root: "pages/test.tsx".into(),
-                font_loaders: vec!["@next/font/google".into(), "cool-fonts".into()],
-            })
+            next_font_loaders(
+                FontLoaderConfig {
+                    relative_file_path_from_root: "pages/test.tsx".into(),
+                    font_loaders: vec!["@next/font/google".into(), "cool-fonts".into()],
+                    query_encoding: Default::default(),
+                    argument_schemas: Default::default(),
+                },
+                Default::default(),
+                Default::default(),
+            )
         },
         &input,
         &output,
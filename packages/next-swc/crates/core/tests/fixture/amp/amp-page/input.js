@@ -0,0 +1,14 @@
+export const config = { amp: true }
+
+export default function Page() {
+  return (
+    <form className="form">
+      <label htmlFor="name" className="label">
+        Name
+      </label>
+      <amp-img className="img" src="/a.png" />
+      <Custom className="custom" htmlFor="name" />
+      <script type="application/ld+json">{'{}'}</script>
+    </form>
+  )
+}
@@ -0,0 +1,8 @@
+'use client'
+import dynamic from 'next/dynamic'
+
+const DynamicComponent = dynamic(() => import('../components/hello'))
+const DynamicClientOnlyComponent = dynamic(
+  () => import('../components/hello'),
+  { ssr: false }
+)
@@ -0,0 +1,17 @@
+'use client';
+import dynamic from 'next/dynamic';
+const DynamicComponent = dynamic(()=>import('../components/hello'), {
+    loadableGenerated: {
+        modules: [
+            "some-file.js -> " + "../components/hello"
+        ]
+    }
+});
+const DynamicClientOnlyComponent = dynamic(()=>import('../components/hello'), {
+    loadableGenerated: {
+        modules: [
+            "some-file.js -> " + "../components/hello"
+        ]
+    },
+    ssr: false
+});
@@ -0,0 +1,16 @@
+import dynamic from 'next/dynamic';
+const DynamicComponent = dynamic(()=>import('../components/hello'), {
+    loadableGenerated: {
+        modules: [
+            __turbopack_module_id__("../components/hello")
+        ]
+    },
+    ssr: false
+});
+const Locale = dynamic(()=>import(`../locales/${lang}`), {
+    loadableGenerated: {
+        modules: [
+            __turbopack_module_id__(`../locales/${lang}`)
+        ]
+    }
+});
@@ -0,0 +1,8 @@
+import * as d from 'next/dynamic'
+import { default as lazy } from 'next/dynamic'
+
+const DynamicComponent = d.default(() => import('../components/hello'))
+const DynamicClientOnlyComponent = lazy(() => import('../components/hello'), {
+  ssr: false,
+})
+const NotDynamic = d.noop(() => import('../components/hello'))
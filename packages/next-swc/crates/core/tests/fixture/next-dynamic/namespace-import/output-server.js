@@ -0,0 +1,18 @@
+import * as d from 'next/dynamic';
+import { default as lazy } from 'next/dynamic';
+const DynamicComponent = d.default(()=>import('../components/hello'), {
+    loadableGenerated: {
+        modules: [
+            "some-file.js -> " + "../components/hello"
+        ]
+    }
+});
+const DynamicClientOnlyComponent = lazy(null, {
+    loadableGenerated: {
+        modules: [
+            "some-file.js -> " + "../components/hello"
+        ]
+    },
+    ssr: false
+});
+const NotDynamic = d.noop(()=>import('../components/hello'));
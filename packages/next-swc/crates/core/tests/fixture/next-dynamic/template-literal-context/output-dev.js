@@ -0,0 +1,8 @@
+import dynamic from 'next/dynamic';
+const Messages = dynamic(()=>import(`../locales/${lang}/messages`), {
+    loadableGenerated: {
+        modules: [
+            "some-file.js -> " + "../locales/*/messages"
+        ]
+    }
+});
@@ -0,0 +1,8 @@
+import dynamic from 'next/dynamic';
+const Messages = dynamic(()=>import(`../locales/${lang}/messages`), {
+    loadableGenerated: {
+        webpack: ()=>[
+                require.resolveWeak(`../locales/${lang}/messages`)
+            ]
+    }
+});
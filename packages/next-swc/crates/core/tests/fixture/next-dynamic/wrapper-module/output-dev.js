@@ -0,0 +1,19 @@
+import { dynamic } from '@acme/ui';
+import * as ui from '@acme/ui';
+import { dynamic as notDynamic } from 'other-ui';
+const DynamicComponent = dynamic(()=>import('../components/hello'), {
+    loadableGenerated: {
+        modules: [
+            "some-file.js -> " + "../components/hello"
+        ]
+    }
+});
+const DynamicClientOnlyComponent = ui.dynamic(()=>import('../components/hello'), {
+    loadableGenerated: {
+        modules: [
+            "some-file.js -> " + "../components/hello"
+        ]
+    },
+    ssr: false
+});
+const NotDynamic = notDynamic(()=>import('../components/hello'));
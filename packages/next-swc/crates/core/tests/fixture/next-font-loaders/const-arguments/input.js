@@ -0,0 +1,16 @@
+import { Inter } from '@next/font/google'
+
+const weights = ['400', '700']
+const subsets = [`latin`]
+const display = 'swap'
+const options = {
+  weight: weights,
+  subsets,
+  display,
+  adjustFontFallback: null,
+  offset: -1,
+}
+
+const inter = Inter(options)
+
+console.log(inter)
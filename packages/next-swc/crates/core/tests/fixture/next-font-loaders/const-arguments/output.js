@@ -0,0 +1,17 @@
+import inter from '@next/font/google/target.css?{"path":"pages/test.tsx","import":"Inter","arguments":[{"weight":["400","700"],"subsets":["latin"],"display":"swap","adjustFontFallback":null,"offset":-1.0}],"variableName":"inter"}';
+const weights = [
+    '400',
+    '700'
+];
+const subsets = [
+    `latin`
+];
+const display = 'swap';
+const options = {
+    weight: weights,
+    subsets,
+    display,
+    adjustFontFallback: null,
+    offset: -1
+};
+console.log(inter);
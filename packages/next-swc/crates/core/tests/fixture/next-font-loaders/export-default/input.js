@@ -0,0 +1,6 @@
+import { Inter, Roboto } from '@next/font/google'
+
+const roboto = Roboto({ weight: '400' })
+
+export { roboto as sans }
+export default Inter({ subsets: ['latin'] })
@@ -0,0 +1,4 @@
+import roboto from '@next/font/google/target.css?{"path":"pages/test.tsx","import":"Roboto","arguments":[{"weight":"400"}],"variableName":"roboto"}';
+import _default from '@next/font/google/target.css?{"path":"pages/test.tsx","import":"Inter","arguments":[{"subsets":["latin"]}],"variableName":"default"}';
+export { roboto as sans };
+export default _default;
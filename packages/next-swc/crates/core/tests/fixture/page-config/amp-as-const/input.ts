@@ -0,0 +1 @@
+export const config = { amp: true } as const
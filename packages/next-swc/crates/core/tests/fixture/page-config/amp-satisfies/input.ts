@@ -0,0 +1,6 @@
+import type { PageConfig } from 'next'
+
+export const config = {
+  amp: true,
+  runtime: 'nodejs' as const,
+} satisfies PageConfig
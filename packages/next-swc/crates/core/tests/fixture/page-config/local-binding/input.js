@@ -0,0 +1,3 @@
+const config = { amp: true }
+
+export { config }
@@ -0,0 +1 @@
+const __NEXT_DROP_CLIENT_FILE__ = "__NEXT_DROP_CLIENT_FILE__ mock_timestamp";
\ No newline at end of file
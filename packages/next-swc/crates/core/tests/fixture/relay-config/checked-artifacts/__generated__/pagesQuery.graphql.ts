@@ -0,0 +1,66 @@
+/**
+ * @generated SignedSource<<0e4f3cbd4b8d1e4f6a3b0ac7c8d2a9f1>>
+ * @lightSyntaxTransform
+ * @nogrep
+ */
+
+/* tslint:disable */
+/* eslint-disable */
+// @ts-nocheck
+
+import { ConcreteRequest, Query } from 'relay-runtime';
+export type pagesQuery$variables = {};
+export type pagesQuery$data = {
+  readonly hello: string | null;
+};
+export type pagesQuery = {
+  response: pagesQuery$data;
+  variables: pagesQuery$variables;
+};
+
+const node: ConcreteRequest = {
+  "fragment": {
+    "argumentDefinitions": [],
+    "kind": "Fragment",
+    "metadata": null,
+    "name": "pagesQuery",
+    "selections": [
+      {
+        "alias": null,
+        "args": null,
+        "kind": "ScalarField",
+        "name": "hello",
+        "storageKey": null
+      }
+    ],
+    "type": "Query",
+    "abstractKey": null
+  },
+  "kind": "Request",
+  "operation": {
+    "argumentDefinitions": [],
+    "kind": "Operation",
+    "name": "pagesQuery",
+    "selections": [
+      {
+        "alias": null,
+        "args": null,
+        "kind": "ScalarField",
+        "name": "hello",
+        "storageKey": null
+      }
+    ]
+  },
+  "params": {
+    "cacheID": "7edd1dee57990429b5968ea257446a68",
+    "id": null,
+    "metadata": {},
+    "name": "pagesQuery",
+    "operationKind": "query",
+    "text": "query pagesQuery {\n  hello\n}\n"
+  }
+};
+
+(node as any).hash = "7edd1dee57990429b5968ea257446a68";
+
+export default node;
@@ -0,0 +1,68 @@
+/**
+ * @generated SignedSource<<5b0b1f9a8e0c1d7f3e2a6c4d9b8e7f10>>
+ * @relayHash 6a795b84b71d9ad7109f9a2c1ba4a301
+ * @lightSyntaxTransform
+ * @nogrep
+ */
+
+/* tslint:disable */
+/* eslint-disable */
+// @ts-nocheck
+
+// @relayRequestID 6a795b84b71d9ad7109f9a2c1ba4a301
+
+import { ConcreteRequest, Query } from 'relay-runtime';
+export type pagesQuery$variables = {};
+export type pagesQuery$data = {
+  readonly hello: string | null;
+};
+export type pagesQuery = {
+  response: pagesQuery$data;
+  variables: pagesQuery$variables;
+};
+
+const node: ConcreteRequest = {
+  "fragment": {
+    "argumentDefinitions": [],
+    "kind": "Fragment",
+    "metadata": null,
+    "name": "pagesQuery",
+    "selections": [
+      {
+        "alias": null,
+        "args": null,
+        "kind": "ScalarField",
+        "name": "hello",
+        "storageKey": null
+      }
+    ],
+    "type": "Query",
+    "abstractKey": null
+  },
+  "kind": "Request",
+  "operation": {
+    "argumentDefinitions": [],
+    "kind": "Operation",
+    "name": "pagesQuery",
+    "selections": [
+      {
+        "alias": null,
+        "args": null,
+        "kind": "ScalarField",
+        "name": "hello",
+        "storageKey": null
+      }
+    ]
+  },
+  "params": {
+    "id": "6a795b84b71d9ad7109f9a2c1ba4a301",
+    "metadata": {},
+    "name": "pagesQuery",
+    "operationKind": "query",
+    "text": null
+  }
+};
+
+(node as any).hash = "7edd1dee57990429b5968ea257446a68";
+
+export default node;
@@ -0,0 +1,82 @@
+/**
+ * @generated SignedSource<<0c3f7a1d9e2b4c6a8d0f1e3b5a7c9d2e>>
+ * @lightSyntaxTransform
+ * @nogrep
+ */
+
+/* tslint:disable */
+/* eslint-disable */
+// @ts-nocheck
+
+import { ConcreteRequest, Query } from 'relay-runtime';
+export type unpersistedQuery$variables = {};
+export type unpersistedQuery$data = {
+  readonly user: {
+    readonly name: string | null;
+  } | null;
+};
+export type unpersistedQuery = {
+  response: unpersistedQuery$data;
+  variables: unpersistedQuery$variables;
+};
+
+const node: ConcreteRequest = (function(){
+var v0 = [
+  {
+    "alias": null,
+    "args": [
+      {
+        "kind": "Literal",
+        "name": "where",
+        "value": {
+          "id": "4"
+        }
+      }
+    ],
+    "concreteType": "User",
+    "kind": "LinkedField",
+    "name": "user",
+    "plural": false,
+    "selections": [
+      {
+        "alias": null,
+        "args": null,
+        "kind": "ScalarField",
+        "name": "name",
+        "storageKey": null
+      }
+    ],
+    "storageKey": "user(where:{\"id\":\"4\"})"
+  }
+];
+return {
+  "fragment": {
+    "argumentDefinitions": [],
+    "kind": "Fragment",
+    "metadata": null,
+    "name": "unpersistedQuery",
+    "selections": (v0/*: any*/),
+    "type": "Query",
+    "abstractKey": null
+  },
+  "kind": "Request",
+  "operation": {
+    "argumentDefinitions": [],
+    "kind": "Operation",
+    "name": "unpersistedQuery",
+    "selections": (v0/*: any*/)
+  },
+  "params": {
+    "cacheID": "3d0c8a3e0fd1a0f1b5e9f3c2a7d4b6e8",
+    "id": null,
+    "metadata": {},
+    "name": "unpersistedQuery",
+    "operationKind": "query",
+    "text": "query unpersistedQuery {\n  user(where: {id: \"4\"}) {\n    name\n  }\n}\n"
+  }
+};
+})();
+
+(node as any).hash = "5f1e0b2c7a9d3e4f6a8b0c1d2e3f4a5b";
+
+export default node;
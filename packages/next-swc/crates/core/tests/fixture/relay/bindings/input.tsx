@@ -0,0 +1,42 @@
+import { graphql as gql } from 'react-relay'
+import * as RelayRuntime from 'relay-runtime'
+import graphqlMacro from '@acme/relay'
+import styled from 'styled-components'
+
+const aliased = gql`
+  query BindingsAliasedQuery {
+    hello
+  }
+`
+
+const namespaced = RelayRuntime.graphql`
+  fragment BindingsNamespacedFragment on User {
+    name
+  }
+`
+
+const configured = graphqlMacro`
+  query BindingsConfiguredQuery {
+    hello
+  }
+`
+
+const notRelay = styled.graphql`
+  query BindingsStyledQuery {
+    hello
+  }
+`
+
+function shadowed(gql) {
+  return gql`
+    query BindingsShadowedQuery {
+      hello
+    }
+  `
+}
+
+const global = graphql`
+  query BindingsGlobalQuery {
+    hello
+  }
+`
@@ -0,0 +1,24 @@
+import { graphql as gql } from 'react-relay';
+import * as RelayRuntime from 'relay-runtime';
+import graphqlMacro from '@acme/relay';
+import styled from 'styled-components';
+const aliased = require("$DIR/__generated__/BindingsAliasedQuery.graphql.ts");
+const namespaced = require("$DIR/__generated__/BindingsNamespacedFragment.graphql.ts");
+const configured = require("$DIR/__generated__/BindingsConfiguredQuery.graphql.ts");
+const notRelay = styled.graphql`
+  query BindingsStyledQuery {
+    hello
+  }
+`;
+function shadowed(gql) {
+    return gql`
+    query BindingsShadowedQuery {
+      hello
+    }
+  `;
+}
+const global = graphql`
+  query BindingsGlobalQuery {
+    hello
+  }
+`;
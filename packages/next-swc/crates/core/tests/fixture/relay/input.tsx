@@ -1,3 +1,5 @@
+import { graphql } from 'react-relay'
+
 const variableQuery = graphql`
   query InputVariableQuery {
     hello
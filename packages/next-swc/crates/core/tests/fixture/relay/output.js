@@ -1,3 +1,4 @@
+import { graphql } from 'react-relay';
 const variableQuery = require("$DIR/__generated__/InputVariableQuery.graphql.ts");
 fetchQuery(require("$DIR/__generated__/InputUsedInFunctionCallQuery.graphql.ts"));
 function SomeQueryComponent() {
@@ -0,0 +1,28 @@
+export function conditional(cond, x) {
+  cond && console.log(x);
+  return cond ? console.warn("warning") : x;
+}
+
+export const arrow = (x) => console.log(x);
+
+export const arrowWithBlock = (x) => {
+  const log = () => console.debug(x);
+  return log;
+};
+
+export const shadowedInArrowParam = (console) => console.log("");
+
+export const shadowedInArrowBody = () => {
+  const console = { log() {} };
+  return () => console.log("");
+};
+
+export function sequence(x) {
+  return console.info(x), x;
+}
+
+export function sideEffects(cond, a, b) {
+  cond && console.log(a(), "message", b, `${b}`);
+}
+
+export const spread = (xs) => console.log(...xs, "message");
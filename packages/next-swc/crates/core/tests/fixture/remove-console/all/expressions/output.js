@@ -0,0 +1,25 @@
+export function conditional(cond, x) {
+    cond && void 0;
+    return cond ? void 0 : x;
+}
+export const arrow = (x)=>void 0;
+export const arrowWithBlock = (x)=>{
+    const log = ()=>void 0;
+    return log;
+};
+export const shadowedInArrowParam = (console)=>console.log("");
+export const shadowedInArrowBody = ()=>{
+    const console = {
+        log () {}
+    };
+    return ()=>console.log("");
+};
+export function sequence(x) {
+    return void 0, x;
+}
+export function sideEffects(cond, a, b) {
+    cond && (a(), void 0);
+}
+export const spread = (xs)=>([
+        ...xs
+    ], void 0);
@@ -0,0 +1,22 @@
+import log from "@acme/log";
+import { logger } from "@acme/logger";
+
+export function localLogger() {
+  const log = { debug() {} };
+  log.debug("kept");
+}
+
+export function nestedLogger() {
+  const logger = createLogger();
+  return function inner() {
+    logger.debug("kept");
+  };
+}
+
+export const arrowLogger = () => {
+  let log = createLogger();
+  log.debug("kept");
+};
+
+log.debug("removed");
+logger.debug("removed");
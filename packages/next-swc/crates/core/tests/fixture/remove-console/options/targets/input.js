@@ -0,0 +1,25 @@
+import log, { debug, info } from "@acme/log";
+import * as acmeLog from "@acme/log";
+import { logger } from "@acme/logger";
+
+console.log("removed");
+console.error("kept");
+
+log.debug("removed");
+log.info("kept");
+acmeLog.trace("removed");
+debug("removed");
+info("kept");
+logger.debug("removed");
+logger.trace("kept");
+
+export function debugging(cond) {
+  debugger;
+  cond && logger.debug("removed");
+}
+
+export function shadowedLogger(logger) {
+  logger.debug("kept");
+}
+
+export const shadowedMethod = (debug) => debug("kept");
@@ -0,0 +1,20 @@
+import log, { debug, info } from "@acme/log";
+import * as acmeLog from "@acme/log";
+import { logger } from "@acme/logger";
+;
+console.error("kept");
+;
+log.info("kept");
+;
+;
+info("kept");
+;
+logger.trace("kept");
+export function debugging(cond) {
+    ;
+    cond && void 0;
+}
+export function shadowedLogger(logger) {
+    logger.debug("kept");
+}
+export const shadowedMethod = (debug)=>debug("kept");
@@ -0,0 +1,18 @@
+import db from 'db'
+import { siteName } from '../lib/site'
+import { Title } from '../components/title'
+
+const fetchProduct = (id) => db.product.find(id)
+
+async function generateMetadata({ params }) {
+  const product = await fetchProduct(params.id)
+  return { title: `${product.name} | ${siteName}` }
+}
+
+export const generateSitemaps = () => [{ id: 0 }]
+
+export { generateMetadata }
+
+export default function Product() {
+  return <Title>{siteName}</Title>
+}
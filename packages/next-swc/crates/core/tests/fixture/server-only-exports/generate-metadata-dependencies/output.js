@@ -0,0 +1,5 @@
+import { siteName } from '../lib/site';
+import { Title } from '../components/title';
+export default function Product() {
+    return <Title>{siteName}</Title>;
+}
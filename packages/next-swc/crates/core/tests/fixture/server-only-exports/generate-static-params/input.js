@@ -0,0 +1,13 @@
+'use client'
+
+import { getAllPosts } from '../lib/posts'
+import { Post } from '../components/post'
+
+export async function generateStaticParams() {
+  const posts = await getAllPosts()
+  return posts.map((post) => ({ slug: post.slug }))
+}
+
+export default function Page({ params }) {
+  return <Post slug={params.slug} />
+}
@@ -0,0 +1,5 @@
+'use client';
+import { Post } from '../components/post';
+export default function Page({ params }) {
+    return <Post slug={params.slug}/>;
+}
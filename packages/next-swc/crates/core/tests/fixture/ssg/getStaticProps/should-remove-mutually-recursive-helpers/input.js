@@ -0,0 +1,20 @@
+import { parse } from 'parser'
+import { format } from 'formatter'
+
+function isEven(n) {
+  return n === 0 ? true : isOdd(n - 1)
+}
+
+function isOdd(n) {
+  return n === 0 ? false : isEven(n - 1)
+}
+
+const load = () => parse(isEven(2))
+
+export async function getStaticProps() {
+  return { props: { value: load() } }
+}
+
+export default function Home({ value }) {
+  return <div>{format(value)}</div>
+}
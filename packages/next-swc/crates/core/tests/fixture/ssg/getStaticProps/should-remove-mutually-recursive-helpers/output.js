@@ -0,0 +1,5 @@
+import { format } from 'formatter';
+export var __N_SSG = true;
+export default function Home({ value }) {
+    return __jsx("div", null, format(value));
+}
@@ -0,0 +1,275 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use next_swc::next_font_loaders::{next_font_loaders, Config, FontManifestEntry, QueryEncoding};
+use serde_json::json;
+use sha1::{Digest, Sha1};
+
+mod common;
+
+const VALID_LEAVES: &[&str] = &[
+    "'latin'", "\"swap\"", "`400`", "1", "-2.5", "0x10", "-0", "true", "false", "null", "valid",
+];
+
+const INVALID_LEAVES: &[&str] = &[
+    "NaN",
+    "Infinity",
+    "-Infinity",
+    "1e999",
+    "-1e999",
+    "10n",
+    "/latin/g",
+    "undefined",
+    "missing",
+    "cyclic",
+    "`${valid}`",
+    "-'1'",
+    "-[]",
+    "() => {}",
+    "[, 1]",
+    "[...[]]",
+    "a.b",
+];
+
+/// A xorshift generator, so failures are reproducible from the seed.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+/// Generates a font loader argument, and whether it converts to JSON.
+fn arbitrary_expr(rng: &mut Rng, depth: usize) -> (String, bool) {
+    let kind = if depth == 0 {
+        rng.below(2)
+    } else {
+        rng.below(4)
+    };
+    match kind {
+        0 => (
+            VALID_LEAVES[rng.below(VALID_LEAVES.len())].to_string(),
+            true,
+        ),
+        1 if rng.below(4) == 0 => (
+            INVALID_LEAVES[rng.below(INVALID_LEAVES.len())].to_string(),
+            false,
+        ),
+        1 => (
+            VALID_LEAVES[rng.below(VALID_LEAVES.len())].to_string(),
+            true,
+        ),
+        2 => {
+            let mut valid = true;
+            let elems: Vec<String> = (0..rng.below(4))
+                .map(|_| {
+                    let (elem, elem_valid) = arbitrary_expr(rng, depth - 1);
+                    valid &= elem_valid;
+                    elem
+                })
+                .collect();
+            (format!("[{}]", elems.join(", ")), valid)
+        }
+        _ => {
+            let mut valid = true;
+            let props: Vec<String> = (0..rng.below(4))
+                .map(|i| {
+                    let key = match rng.below(7) {
+                        0 => format!("key{}", i),
+                        1 => format!("'key-{}'", i),
+                        2 => format!("{}", i),
+                        3 => format!("{}.5", i),
+                        4 => return "valid".to_string(),
+                        5 => {
+                            valid = false;
+                            "[key]".to_string()
+                        }
+                        _ => {
+                            valid = false;
+                            return "...{}".to_string();
+                        }
+                    };
+                    let (value, value_valid) = arbitrary_expr(rng, depth - 1);
+                    valid &= value_valid;
+                    format!("{}: {}", key, value)
+                })
+                .collect();
+            (format!("{{ {} }}", props.join(", ")), valid)
+        }
+    }
+}
+
+fn transform(
+    src: &str,
+    query_encoding: QueryEncoding,
+    font_queries: Rc<RefCell<BTreeMap<String, String>>>,
+    font_manifest: Rc<RefCell<Vec<FontManifestEntry>>>,
+) -> Result<String, ()> {
+    common::transform("pages/index.js", src, || {
+        next_font_loaders(
+            Config {
+                font_loaders: vec!["@next/font/google".into(), "@next/font/local".into()],
+                relative_file_path_from_root: "pages/index.js".into(),
+                query_encoding,
+                argument_schemas: Default::default(),
+            },
+            font_queries,
+            font_manifest,
+        )
+    })
+}
+
+#[test]
+fn should_convert_arbitrary_arguments_without_panicking() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..1000 {
+        let (arg, valid) = arbitrary_expr(&mut rng, 4);
+        let src = format!(
+            "import {{ Inter }} from '@next/font/google'\nconst valid = ['400']\nconst cyclic = \
+             [cyclic]\nconst inter = Inter({})\n",
+            arg
+        );
+
+        let result = transform(
+            &src,
+            QueryEncoding::Json,
+            Default::default(),
+            Default::default(),
+        );
+        assert_eq!(result.is_ok(), valid, "unexpected result for `{}`", arg);
+        if let Ok(code) = result {
+            assert!(code.contains("@next/font/google/target.css?"), "{}", code);
+        }
+    }
+}
+
+const SPECIAL_CHARACTERS: &str =
+    "import { Inter } from '@next/font/google'\nconst inter = Inter({ family: 'Noto #1 & ü?' })\n";
+
+const SPECIAL_CHARACTERS_QUERY: &str = r#"{"path":"pages/index.js","import":"Inter","arguments":[{"family":"Noto #1 & ü?"}],"variableName":"inter"}"#;
+
+#[test]
+fn should_url_encode_font_queries() {
+    let code = transform(
+        SPECIAL_CHARACTERS,
+        QueryEncoding::UrlEncoded,
+        Default::default(),
+        Default::default(),
+    )
+    .unwrap();
+    assert!(code.contains(&format!(
+        "@next/font/google/target.css?{}",
+        urlencoding::encode(SPECIAL_CHARACTERS_QUERY)
+    )));
+}
+
+#[test]
+fn should_base64_encode_font_queries() {
+    let code = transform(
+        SPECIAL_CHARACTERS,
+        QueryEncoding::Base64Url,
+        Default::default(),
+        Default::default(),
+    )
+    .unwrap();
+    assert!(code.contains(&format!(
+        "@next/font/google/target.css?{}",
+        URL_SAFE_NO_PAD.encode(SPECIAL_CHARACTERS_QUERY)
+    )));
+}
+
+#[test]
+fn should_hash_font_queries() {
+    let font_queries: Rc<RefCell<BTreeMap<String, String>>> = Default::default();
+    let code = transform(
+        SPECIAL_CHARACTERS,
+        QueryEncoding::Hash,
+        font_queries.clone(),
+        Default::default(),
+    )
+    .unwrap();
+    let hash = hex::encode(Sha1::digest(SPECIAL_CHARACTERS_QUERY.as_bytes()));
+    assert!(code.contains(&format!("@next/font/google/target.css?{}", hash)));
+    assert_eq!(
+        *font_queries.borrow(),
+        BTreeMap::from([(hash, SPECIAL_CHARACTERS_QUERY.to_string())])
+    );
+}
+
+#[test]
+fn should_collect_font_manifest() {
+    let font_manifest: Rc<RefCell<Vec<FontManifestEntry>>> = Default::default();
+    transform(
+        r#"import { Inter } from '@next/font/google'
+import localFont from '@next/font/local'
+
+const inter = Inter({ weight: ['400', '700'], subsets: ['latin'] })
+export const myFont = localFont({ src: './my-font.woff2' })
+"#,
+        QueryEncoding::Json,
+        Default::default(),
+        font_manifest.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        serde_json::to_value(&*font_manifest.borrow()).unwrap(),
+        json!([
+            {
+                "loader": "@next/font/google",
+                "functionName": "Inter",
+                "arguments": [{ "weight": ["400", "700"], "subsets": ["latin"] }],
+                "variableName": "inter",
+                "exported": false,
+            },
+            {
+                "loader": "@next/font/local",
+                "functionName": null,
+                "arguments": [{ "src": "./my-font.woff2" }],
+                "variableName": "myFont",
+                "exported": true,
+            },
+        ])
+    );
+}
+
+#[test]
+fn should_mark_re_exported_fonts_in_font_manifest() {
+    let font_manifest: Rc<RefCell<Vec<FontManifestEntry>>> = Default::default();
+    transform(
+        r#"import { Inter, Roboto } from '@next/font/google'
+
+const inter = Inter()
+const roboto = Roboto()
+
+export { inter as sans }
+export default Roboto({ weight: '400' })
+"#,
+        QueryEncoding::Json,
+        Default::default(),
+        font_manifest.clone(),
+    )
+    .unwrap();
+    let exported: Vec<(String, bool)> = font_manifest
+        .borrow()
+        .iter()
+        .map(|entry| (entry.variable_name.clone(), entry.exported))
+        .collect();
+    assert_eq!(
+        exported,
+        vec![
+            ("inter".to_string(), true),
+            ("roboto".to_string(), false),
+            ("default".to_string(), true),
+        ]
+    );
+}
@@ -58,6 +58,8 @@ fn test(input: &Path, minify: bool) {
                 },
                 disable_next_ssg: false,
                 disable_page_config: false,
+                deterministic_amp_marker: false,
+                server_only_exports: None,
                 pages_dir: None,
                 is_page_file: false,
                 is_development: true,
@@ -65,6 +67,7 @@ fn test(input: &Path, minify: bool) {
                 server_components: None,
                 styled_components: Some(assert_json("{}")),
                 styled_jsx: true,
+                next_dynamic: None,
                 remove_console: None,
                 react_remove_properties: None,
                 relay: None,
@@ -92,6 +95,10 @@ fn test(input: &Path, minify: bool) {
                         &options,
                         comments.clone(),
                         Default::default(),
+                        Default::default(),
+                        Default::default(),
+                        Default::default(),
+                        Default::default(),
                     )
                 },
                 |_| noop(),
@@ -0,0 +1,53 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use next_swc::next_dynamic::{next_dynamic, LoadableManifestEntry};
+
+use next_binding::swc::core::common::FileName;
+
+mod common;
+
+#[test]
+fn should_collect_loadable_manifest_entries() {
+    let loadable_manifest: Rc<RefCell<Vec<LoadableManifestEntry>>> = Default::default();
+    let source = r#"import dynamic from 'next/dynamic'
+
+const Hello = dynamic(() => import('../components/hello'), {
+  loading: () => null,
+})
+const Locale = dynamic(() => import(`../locales/${lang}`), { ssr: false })
+"#;
+    let fm = common::new_source_file("/some-project/src/some-file.js", source);
+    let start_pos = fm.start_pos;
+    assert!(common::run_pass(fm, || {
+        next_dynamic(
+            true,
+            false,
+            false,
+            FileName::Real(PathBuf::from("/some-project/src/some-file.js")),
+            Some("/some-project/src".into()),
+            None,
+            Default::default(),
+            loadable_manifest.clone(),
+        )
+    })
+    .is_ok());
+
+    let entries = loadable_manifest.borrow();
+    assert_eq!(
+        entries
+            .iter()
+            .map(|entry| (entry.specifier.as_str(), entry.ssr, entry.has_loading))
+            .collect::<Vec<_>>(),
+        vec![
+            ("../components/hello", None, true),
+            ("../locales/*", Some(false), false),
+        ]
+    );
+    assert_eq!(
+        &source[(entries[1].span.lo - start_pos).0 as usize
+            ..(entries[1].span.hi - start_pos).0 as usize],
+        "dynamic(() => import(`../locales/${lang}`), { ssr: false })"
+    );
+}
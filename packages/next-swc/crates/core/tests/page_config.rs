@@ -0,0 +1,93 @@
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use next_swc::page_config::{content_hash_marker, page_config, PageConfigExport};
+use serde_json::json;
+
+use next_binding::swc::core::common::FileName;
+
+mod common;
+
+#[test]
+fn should_extract_page_config() {
+    let extracted: Rc<RefCell<Option<PageConfigExport>>> = Default::default();
+    assert!(common::transform(
+        "pages/api/upload.js",
+        r#"export const config = {
+  runtime: 'nodejs',
+  regions: ['iad1', 'sfo1'],
+  maxDuration: 30,
+  api: {
+    bodyParser: { sizeLimit: '1mb' },
+    externalResolver: true,
+    responseLimit: false,
+  },
+  'unstable_includeFiles': ['data/**/*.json'],
+}
+
+export default function handler(req, res) {}
+"#,
+        || page_config(false, true, None, extracted.clone()),
+    )
+    .is_ok());
+    assert_eq!(
+        serde_json::to_value(extracted.borrow().as_ref().unwrap()).unwrap(),
+        json!({
+            "runtime": "nodejs",
+            "regions": ["iad1", "sfo1"],
+            "maxDuration": 30.0,
+            "api": {
+                "bodyParser": { "sizeLimit": "1mb" },
+                "externalResolver": true,
+                "responseLimit": false,
+            },
+            "unstable_includeFiles": ["data/**/*.json"],
+        })
+    );
+}
+
+fn transform_amp_page(file_name: &str, src: &str) -> String {
+    let marker = content_hash_marker(
+        &FileName::Real(file_name.into()),
+        Some(Path::new("/project/pages")),
+        src,
+    );
+    common::transform(file_name, src, || {
+        page_config(false, true, Some(marker), Default::default())
+    })
+    .unwrap()
+}
+
+#[test]
+fn should_derive_drop_bundle_marker_from_source() {
+    let src = "export const config = { amp: true }\nexport default () => null\n";
+    let other_src = "export const config = { amp: true }\nexport default () => 'amp'\n";
+
+    let code = transform_amp_page("/project/pages/amp.js", src);
+    assert!(code.contains(&format!(
+        "__NEXT_DROP_CLIENT_FILE__ {}",
+        content_hash_marker(&FileName::Real("amp.js".into()), None, src)
+    )));
+    assert_eq!(code, transform_amp_page("/project/pages/amp.js", src));
+    assert_ne!(code, transform_amp_page("/project/pages/amp.js", other_src));
+    assert_ne!(code, transform_amp_page("/project/pages/other.js", src));
+}
+
+#[test]
+fn should_derive_drop_bundle_marker_from_path_relative_to_pages_dir() {
+    let src = "export const config = { amp: true }\nexport default () => null\n";
+
+    assert_eq!(
+        content_hash_marker(
+            &FileName::Real("/a/project/pages/amp.js".into()),
+            Some(Path::new("/a/project/pages")),
+            src
+        ),
+        content_hash_marker(
+            &FileName::Real("/b/project/pages/amp.js".into()),
+            Some(Path::new("/b/project/pages")),
+            src
+        )
+    );
+}
@@ -0,0 +1,203 @@
+use std::path::{Path, PathBuf};
+
+use next_swc::relay::{relay, ArtifactCheck, Config, RelayLanguageConfig};
+
+use next_binding::swc::core::common::FileName;
+
+mod common;
+
+fn fixture_dir(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixture/relay-config")
+        .join(name)
+}
+
+fn try_transform(config: &Config, file_name: &str, src: &str) -> Result<String, ()> {
+    common::transform(file_name, src, || {
+        relay(config, FileName::Real(file_name.into()), None)
+    })
+}
+
+fn transform(config: &Config, file_name: &str, src: &str) -> String {
+    try_transform(config, file_name, src).unwrap()
+}
+
+fn query(name: &str) -> String {
+    format!(
+        "import {{ graphql }} from 'react-relay'\nexport default graphql`query {} {{ hello }}`\n",
+        name
+    )
+}
+
+#[test]
+fn should_use_artifact_directory_of_relay_config() {
+    let root_dir = fixture_dir("single-project");
+    let config = Config {
+        root_dir: Some(root_dir.clone()),
+        ..Default::default()
+    };
+
+    let code = transform(&config, "src/pages/index.tsx", &query("pagesQuery"));
+    assert!(code.contains(&format!(
+        "require(\"{}\")",
+        root_dir
+            .join("src/__generated__/pagesQuery.graphql.ts")
+            .display()
+    )));
+}
+
+#[test]
+fn should_prefer_options_over_relay_config() {
+    let root_dir = fixture_dir("single-project");
+
+    let config = Config {
+        root_dir: Some(root_dir.clone()),
+        artifact_directory: Some("generated".into()),
+        ..Default::default()
+    };
+    let code = transform(&config, "src/pages/index.tsx", &query("pagesQuery"));
+    assert!(code.contains(&format!(
+        "require(\"{}\")",
+        root_dir.join("generated/pagesQuery.graphql.ts").display()
+    )));
+
+    let config = Config {
+        root_dir: Some(root_dir.clone()),
+        language: Some(RelayLanguageConfig::JavaScript),
+        ..Default::default()
+    };
+    let code = transform(&config, "src/pages/index.tsx", &query("pagesQuery"));
+    assert!(code.contains(&format!(
+        "require(\"{}\")",
+        root_dir
+            .join("src/__generated__/pagesQuery.graphql.js")
+            .display()
+    )));
+}
+
+#[test]
+fn should_use_project_of_multi_project_relay_config() {
+    let root_dir = fixture_dir("multi-project");
+    let config = Config {
+        root_dir: Some(root_dir.clone()),
+        config_file: Some("relay.config.json".into()),
+        ..Default::default()
+    };
+
+    let code = transform(&config, "project-a/pages/index.tsx", &query("pagesAQuery"));
+    assert!(code.contains(&format!(
+        "require(\"{}\")",
+        root_dir
+            .join("project-a/__generated__/pagesAQuery.graphql.ts")
+            .display()
+    )));
+
+    let code = transform(&config, "project-b/pages/index.tsx", &query("pagesBQuery"));
+    assert!(code.contains("require(\"pagesBQuery.graphql\")"));
+}
+
+#[test]
+fn should_reject_sources_of_several_relay_projects() {
+    let config = Config {
+        root_dir: Some(fixture_dir("multi-project")),
+        ..Default::default()
+    };
+
+    assert!(try_transform(&config, "shared/index.tsx", &query("sharedQuery")).is_err());
+}
+
+#[test]
+fn should_import_artifacts_with_eager_es_modules() {
+    let root_dir = fixture_dir("eager-es-modules");
+    let config = Config {
+        root_dir: Some(root_dir.clone()),
+        ..Default::default()
+    };
+
+    let code = transform(
+        &config,
+        "src/pages/index.tsx",
+        "'use client'\nimport { graphql } from 'react-relay'\nexport const a = graphql`query \
+         pagesQuery { hello }`\nexport const b = graphql`query pagesQuery { hello }`\n",
+    );
+    let import = format!(
+        "import _pagesQuery from \"{}\";",
+        root_dir
+            .join("src/__generated__/pagesQuery.graphql.ts")
+            .display()
+    );
+    assert!(
+        code.starts_with(&format!("'use client';\n{}", import)),
+        "{}",
+        code
+    );
+    assert_eq!(code.matches(&import).count(), 1, "{}", code);
+    assert!(code.contains("export const a = _pagesQuery;"), "{}", code);
+    assert!(code.contains("export const b = _pagesQuery;"), "{}", code);
+    assert!(!code.contains("require("), "{}", code);
+}
+
+#[test]
+fn should_replace_operations_with_persisted_query_ids() {
+    let root_dir = fixture_dir("persisted-queries");
+    let config = Config {
+        root_dir: Some(root_dir.clone()),
+        persisted_queries: true,
+        ..Default::default()
+    };
+
+    let code = transform(
+        &config,
+        "pages/index.tsx",
+        "import { graphql } from 'react-relay'\nexport const query = graphql`query pagesQuery { \
+         hello }`\nexport const fragment = graphql`fragment pages_user on User { name }`\n",
+    );
+    // The `params.id` of the artifact, not a hash of the template.
+    assert!(
+        code.contains("export const query = \"6a795b84b71d9ad7109f9a2c1ba4a301\";"),
+        "{}",
+        code
+    );
+    assert!(code.contains(&format!(
+        "export const fragment = require(\"{}\");",
+        root_dir
+            .join("__generated__/pages_user.graphql.ts")
+            .display()
+    )));
+
+    assert!(try_transform(&config, "pages/index.tsx", &query("missingQuery")).is_err());
+    // Its only `id` is in an argument of a selection, `params.id` is null.
+    assert!(try_transform(&config, "pages/index.tsx", &query("unpersistedQuery")).is_err());
+}
+
+#[test]
+fn should_check_that_artifacts_exist() {
+    let config = Config {
+        root_dir: Some(fixture_dir("checked-artifacts")),
+        check_artifacts: ArtifactCheck::Exists,
+        ..Default::default()
+    };
+
+    assert!(try_transform(&config, "pages/index.tsx", &query("pagesQuery")).is_ok());
+    assert!(try_transform(&config, "pages/index.tsx", &query("missingQuery")).is_err());
+}
+
+#[test]
+fn should_check_artifact_hashes() {
+    let stale = "import { graphql } from 'react-relay'\nexport default graphql`query pagesQuery { \
+                 hello world }`\n";
+
+    let config = Config {
+        root_dir: Some(fixture_dir("checked-artifacts")),
+        check_artifacts: ArtifactCheck::Exists,
+        ..Default::default()
+    };
+    assert!(try_transform(&config, "pages/index.tsx", stale).is_ok());
+
+    let config = Config {
+        check_artifacts: ArtifactCheck::Hash,
+        ..config
+    };
+    assert!(try_transform(&config, "pages/index.tsx", &query("pagesQuery")).is_ok());
+    assert!(try_transform(&config, "pages/index.tsx", stale).is_err());
+}
@@ -32,7 +32,7 @@ DEALINGS IN THE SOFTWARE.
 #[macro_use]
 extern crate napi_derive;
 
-use std::{env, panic::set_hook, sync::Arc};
+use std::{collections::BTreeMap, env, panic::set_hook, sync::Arc};
 
 use backtrace::Backtrace;
 use fxhash::FxHashSet;
@@ -77,10 +77,15 @@ fn get_compiler() -> Arc<Compiler> {
     COMPILER.clone()
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn complete_output(
     env: &Env,
     output: TransformOutput,
     eliminated_packages: FxHashSet<String>,
+    loadable_manifest: Vec<transform::LoadableManifestItem>,
+    page_config: Option<next_swc::page_config::PageConfigExport>,
+    font_queries: BTreeMap<String, String>,
+    font_manifest: Vec<next_swc::next_font_loaders::FontManifestEntry>,
 ) -> napi::Result<Object> {
     let mut js_output = env.create_object()?;
     js_output.set_named_property("code", env.create_string_from_std(output.code)?)?;
@@ -93,6 +98,30 @@ pub fn complete_output(
             env.create_string_from_std(serde_json::to_string(&eliminated_packages)?)?,
         )?;
     }
+    if !loadable_manifest.is_empty() {
+        js_output.set_named_property(
+            "loadableManifest",
+            env.create_string_from_std(serde_json::to_string(&loadable_manifest)?)?,
+        )?;
+    }
+    if let Some(page_config) = page_config {
+        js_output.set_named_property(
+            "pageConfig",
+            env.create_string_from_std(serde_json::to_string(&page_config)?)?,
+        )?;
+    }
+    if !font_queries.is_empty() {
+        js_output.set_named_property(
+            "fontQueries",
+            env.create_string_from_std(serde_json::to_string(&font_queries)?)?,
+        )?;
+    }
+    if !font_manifest.is_empty() {
+        js_output.set_named_property(
+            "fontManifest",
+            env.create_string_from_std(serde_json::to_string(&font_manifest)?)?,
+        )?;
+    }
     Ok(js_output)
 }
 
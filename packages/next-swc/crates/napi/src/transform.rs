@@ -29,6 +29,7 @@ DEALINGS IN THE SOFTWARE.
 use std::fs::read_to_string;
 use std::{
     cell::RefCell,
+    collections::BTreeMap,
     panic::{catch_unwind, AssertUnwindSafe},
     rc::Rc,
     sync::Arc,
@@ -42,7 +43,11 @@ use next_binding::swc::core::{
     common::{comments::SingleThreadedComments, errors::ColorConfig, FileName, GLOBALS},
     ecma::transforms::base::pass::noop,
 };
-use next_swc::{custom_before_pass, TransformOptions};
+use next_swc::{
+    custom_before_pass, next_dynamic::LoadableManifestEntry, next_font_loaders::FontManifestEntry,
+    page_config::PageConfigExport, TransformOptions,
+};
+use serde::Serialize;
 
 use crate::{complete_output, get_compiler, util::MapErr};
 
@@ -55,6 +60,48 @@ pub enum Input {
     FromFilename,
 }
 
+/// A `next/dynamic` call as returned in `loadableManifest`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadableManifestItem {
+    pub specifier: String,
+    pub ssr: Option<bool>,
+    pub loading: bool,
+    pub start: Position,
+    pub end: Position,
+}
+
+/// 1-based line and 0-based column.
+#[derive(Debug, Serialize)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+fn loadable_manifest_items(
+    c: &Compiler,
+    entries: Vec<LoadableManifestEntry>,
+) -> Vec<LoadableManifestItem> {
+    let position = |pos| {
+        let loc = c.cm.lookup_char_pos(pos);
+        Position {
+            line: loc.line,
+            column: loc.col_display,
+        }
+    };
+
+    entries
+        .into_iter()
+        .map(|entry| LoadableManifestItem {
+            specifier: entry.specifier,
+            ssr: entry.ssr,
+            loading: entry.has_loading,
+            start: position(entry.span.lo),
+            end: position(entry.span.hi),
+        })
+        .collect()
+}
+
 pub struct TransformTask {
     pub c: Arc<Compiler>,
     pub input: Input,
@@ -67,12 +114,23 @@ fn skip_filename() -> bool {
 }
 
 impl Task for TransformTask {
-    type Output = (TransformOutput, FxHashSet<String>);
+    type Output = (
+        TransformOutput,
+        FxHashSet<String>,
+        Vec<LoadableManifestItem>,
+        Option<PageConfigExport>,
+        BTreeMap<String, String>,
+        Vec<FontManifestEntry>,
+    );
     type JsValue = Object;
 
     fn compute(&mut self) -> napi::Result<Self::Output> {
         GLOBALS.set(&Default::default(), || {
             let eliminated_packages: Rc<RefCell<fxhash::FxHashSet<String>>> = Default::default();
+            let loadable_manifest: Rc<RefCell<Vec<LoadableManifestEntry>>> = Default::default();
+            let page_config: Rc<RefCell<Option<PageConfigExport>>> = Default::default();
+            let font_queries: Rc<RefCell<BTreeMap<String, String>>> = Default::default();
+            let font_manifest: Rc<RefCell<Vec<FontManifestEntry>>> = Default::default();
             let res = catch_unwind(AssertUnwindSafe(|| {
                 try_with_handler(
                     self.c.cm.clone(),
@@ -126,6 +184,10 @@ impl Task for TransformTask {
                                         &options,
                                         comments.clone(),
                                         eliminated_packages.clone(),
+                                        loadable_manifest.clone(),
+                                        page_config.clone(),
+                                        font_queries.clone(),
+                                        font_manifest.clone(),
                                     )
                                 },
                                 |_| noop(),
@@ -144,7 +206,19 @@ impl Task for TransformTask {
 
             match res {
                 Ok(res) => res
-                    .map(|o| (o, eliminated_packages.replace(Default::default())))
+                    .map(|o| {
+                        (
+                            o,
+                            eliminated_packages.replace(Default::default()),
+                            loadable_manifest_items(
+                                &self.c,
+                                loadable_manifest.replace(Default::default()),
+                            ),
+                            page_config.take(),
+                            font_queries.take(),
+                            font_manifest.take(),
+                        )
+                    })
                     .convert_err(),
                 Err(err) => Err(napi::Error::new(
                     Status::GenericFailure,
@@ -157,9 +231,17 @@ impl Task for TransformTask {
     fn resolve(
         &mut self,
         env: Env,
-        (output, eliminated_packages): Self::Output,
+        (output, eliminated_packages, loadable_manifest, page_config, font_queries, font_manifest): Self::Output,
     ) -> napi::Result<Self::JsValue> {
-        complete_output(&env, output, eliminated_packages)
+        complete_output(
+            &env,
+            output,
+            eliminated_packages,
+            loadable_manifest,
+            page_config,
+            font_queries,
+            font_manifest,
+        )
     }
 }
 
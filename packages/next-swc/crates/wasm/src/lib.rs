@@ -100,6 +100,10 @@ pub fn transform_sync(s: JsValue, opts: JsValue) -> Result<JsValue, JsValue> {
                                     &opts,
                                     comments.clone(),
                                     Default::default(),
+                                    Default::default(),
+                                    Default::default(),
+                                    Default::default(),
+                                    Default::default(),
                                 )
                             },
                             |_| noop(),
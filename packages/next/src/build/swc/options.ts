@@ -259,6 +259,7 @@ any) {
       '@next/font/google',
     ],
     relativeFilePathFromRoot,
+    queryEncoding: nextConfig?.experimental?.fontQueryEncoding,
   }
 
   const isNextDist = nextDistPath.test(filename)
@@ -65,6 +65,7 @@ export function getNextFontLoader(
       assetPrefix: ctx.assetPrefix,
       fontLoaderPath,
       fontLoaderOptions,
+      queryEncoding: ctx.experimental.fontQueryEncoding,
       postcss,
     },
   })
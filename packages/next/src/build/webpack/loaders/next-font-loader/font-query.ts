@@ -0,0 +1,51 @@
+/**
+ * How next-swc encodes the call of a font function in the resourceQuery of
+ * the generated CSS import, set with `experimental.fontQueryEncoding`.
+ */
+export type FontQueryEncoding = 'json' | 'urlEncoded' | 'base64Url' | 'hash'
+
+// The JSON of the queries next-swc replaced with their hash, by hash.
+const hashedFontQueries = new Map<string, string>()
+
+/**
+ * Records the `fontQueries` returned by next-swc for a module, so that
+ * next-font-loader can decode hashed queries.
+ */
+export function recordFontQueries(fontQueries: string) {
+  for (const [hash, query] of Object.entries<string>(JSON.parse(fontQueries))) {
+    hashedFontQueries.set(hash, query)
+  }
+}
+
+/**
+ * Decodes the resourceQuery of a font loader import, without the leading `?`.
+ */
+export function decodeFontQuery(
+  query: string,
+  encoding: FontQueryEncoding = 'json'
+) {
+  switch (encoding) {
+    case 'json':
+      return JSON.parse(query)
+    case 'urlEncoded':
+      return JSON.parse(decodeURIComponent(query))
+    case 'base64Url':
+      return JSON.parse(
+        Buffer.from(
+          query.replace(/-/g, '+').replace(/_/g, '/'),
+          'base64'
+        ).toString()
+      )
+    case 'hash': {
+      const json = hashedFontQueries.get(query)
+      if (json === undefined) {
+        throw new Error(
+          `Unknown font query hash "${query}". The module importing the font was not transformed by next-swc in this compilation.`
+        )
+      }
+      return JSON.parse(json)
+    }
+    default:
+      throw new Error(`Unknown font query encoding "${encoding}"`)
+  }
+}
@@ -5,6 +5,7 @@ import path from 'path'
 import chalk from 'next/dist/compiled/chalk'
 import loaderUtils from 'next/dist/compiled/loader-utils3'
 import postcssNextFontPlugin from './postcss-next-font'
+import { decodeFontQuery } from './font-query'
 import { promisify } from 'util'
 import { CONFIG_FILES } from '../../../../shared/lib/constants'
 
@@ -22,13 +23,24 @@ export default async function nextFontLoader(this: any) {
      * import inter from 'next/font/google/target.css?{"import":"Inter","subsets":["latin"]}'
      *
      * Here we parse the resourceQuery to get the font function name, call arguments, and the path to the file that called the font function.
+     * The query is JSON unless another encoding is set with `experimental.fontQueryEncoding`.
      */
+    const {
+      isDev,
+      isServer,
+      assetPrefix,
+      fontLoaderPath,
+      fontLoaderOptions,
+      queryEncoding,
+      postcss: getPostcss,
+    } = this.getOptions()
+
     const {
       path: relativeFilePathFromRoot,
       import: functionName,
       arguments: data,
       variableName,
-    } = JSON.parse(this.resourceQuery.slice(1))
+    } = decodeFontQuery(this.resourceQuery.slice(1), queryEncoding)
 
     // Throw error if @next/font is used in _document.js
     if (/pages[\\/]_document\./.test(relativeFilePathFromRoot)) {
@@ -42,15 +54,6 @@ export default async function nextFontLoader(this: any) {
       return
     }
 
-    const {
-      isDev,
-      isServer,
-      assetPrefix,
-      fontLoaderPath,
-      fontLoaderOptions,
-      postcss: getPostcss,
-    } = this.getOptions()
-
     const nextConfigPaths = CONFIG_FILES.map((config) =>
       path.join(this.rootContext, config)
     )
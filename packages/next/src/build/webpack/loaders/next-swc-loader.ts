@@ -28,6 +28,7 @@ DEALINGS IN THE SOFTWARE.
 
 import { isWasm, transform } from '../../swc'
 import { getLoaderSWCOptions } from '../../swc/options'
+import { recordFontQueries } from './next-font-loader/font-query'
 import path, { isAbsolute } from 'path'
 
 async function loaderTransform(
@@ -116,6 +117,9 @@ async function loaderTransform(
           this.eliminatedPackages.add(pkg)
         }
       }
+      if (output.fontQueries) {
+        recordFontQueries(output.fontQueries)
+      }
       return [output.code, output.map ? JSON.parse(output.map) : undefined]
     })
   )
@@ -98,6 +98,34 @@ const configSchema = {
                     minLength: 1,
                   },
                 },
+                targets: {
+                  type: 'array',
+                  items: {
+                    type: 'object',
+                    additionalProperties: false,
+                    required: ['module', 'methods'],
+                    properties: {
+                      module: {
+                        type: 'string',
+                        minLength: 1,
+                      },
+                      export: {
+                        type: 'string',
+                        minLength: 1,
+                      },
+                      methods: {
+                        type: 'array',
+                        items: {
+                          type: 'string',
+                          minLength: 1,
+                        },
+                      },
+                    },
+                  },
+                },
+                removeDebugger: {
+                  type: 'boolean',
+                },
               },
             },
           ] as any,
@@ -434,6 +462,10 @@ const configSchema = {
           },
           type: 'array',
         } as any,
+        fontQueryEncoding: {
+          enum: ['json', 'urlEncoded', 'base64Url', 'hash'],
+          type: 'string',
+        } as any,
         webVitalsAttribution: {
           type: 'array',
           items: {
@@ -8,6 +8,7 @@ import {
 } from '../shared/lib/image-config'
 import { ServerRuntime } from 'next/types'
 import { SubresourceIntegrityAlgorithm } from '../build/webpack/plugins/subresource-integrity-plugin'
+import type { FontQueryEncoding } from '../build/webpack/loaders/next-font-loader/font-query'
 import { WEB_VITALS } from '../shared/lib/utils'
 
 export type NextConfigComplete = Required<NextConfig> & {
@@ -196,6 +197,12 @@ export interface ExperimentalConfig {
 
   fontLoaders?: Array<{ loader: string; options?: any }>
 
+  /**
+   * How the arguments of font function calls are encoded in the imports
+   * generated for them. Defaults to `json`.
+   */
+  fontQueryEncoding?: FontQueryEncoding
+
   webVitalsAttribution?: Array<typeof WEB_VITALS[number]>
 
   turbo?: ExperimentalTurboOptions
@@ -515,14 +522,26 @@ export interface NextConfig extends Record<string, any> {
           properties?: string[]
         }
     relay?: {
-      src: string
+      src?: string
       artifactDirectory?: string
       language?: 'typescript' | 'javascript' | 'flow'
+      eagerEsModules?: boolean
+      rootDir?: string
+      configFile?: string
+      graphqlModules?: string[]
+      persistedQueries?: boolean
+      checkArtifacts?: 'none' | 'exists' | 'hash'
     }
     removeConsole?:
       | boolean
       | {
           exclude?: string[]
+          targets?: {
+            module: string
+            export?: string
+            methods: string[]
+          }[]
+          removeDebugger?: boolean
         }
     styledComponents?:
       | boolean
@@ -0,0 +1,40 @@
+/* eslint-env jest */
+import vm from 'vm'
+import { transform } from 'next/dist/build/swc'
+import {
+  decodeFontQuery,
+  recordFontQueries,
+} from 'next/dist/build/webpack/loaders/next-font-loader/font-query'
+
+const source = `import { Inter } from 'next/font/google'
+const inter = Inter({ family: 'Noto #1 & ü?', weight: ['400', '700'] })
+`
+
+describe('font loader queries', () => {
+  for (const queryEncoding of ['json', 'urlEncoded', 'base64Url', 'hash']) {
+    it(`should decode ${queryEncoding} queries encoded by next-swc`, async () => {
+      const output = await transform(source, {
+        filename: '/project/pages/index.js',
+        fontLoaders: {
+          fontLoaders: ['next/font/google'],
+          relativeFilePathFromRoot: 'pages/index.js',
+          queryEncoding,
+        },
+      })
+      if (output.fontQueries) {
+        recordFontQueries(output.fontQueries)
+      }
+
+      // The string literal of the generated import, in either quote style.
+      const [, literal] = output.code.match(/import inter from (.+);/)
+      const specifier: string = vm.runInNewContext(literal)
+      const query = specifier.slice(specifier.indexOf('?') + 1)
+      expect(decodeFontQuery(query, queryEncoding as any)).toEqual({
+        path: 'pages/index.js',
+        import: 'Inter',
+        arguments: [{ family: 'Noto #1 & ü?', weight: ['400', '700'] }],
+        variableName: 'inter',
+      })
+    })
+  }
+})